@@ -3,10 +3,85 @@
 //! ## Service Impls
 //! * [`LogDebugService`] logs [`Debug`] input at a configured log level to [`log::log`], returning the input as output.
 //! * [`LogDisplayService`] logs [`Display`] input at a configured log level to [`log::log`], returning the input as output.
+//! * [`LogHashService`] logs the hash of [`Hash`] input at a configured log level to [`log::log`], returning the input as output.
+//! * [`LogTeeService`] passes a clone of input to two underlying logging services, returning the input as output.
+//! * [`CountingByLevelLogService`] wraps a [`LogDisplayService`], counting the number of log statements per level.
+//! * [`LogEachDisplayService`] logs each [`Display`] element of [`Vec`] input with its index at a configured log level to [`log::log`], returning the input as output.
+//! * [`LogSlowService`] times an underlying service, logging the elapsed time at a configured log level to [`log::log`] only when it exceeds a threshold.
+//! * [`LogDisplayServiceConst`] logs [`Display`] input at a log level encoded in its type to [`log::log`], returning the input as output.
+//! * [`LogChannelService`] logs [`Display`] input at a configured log level to [`log::log`] and sends the log statement to a channel, returning the input as output.
+//! * [`SwappablePrefixLogDisplayService`] logs [`Display`] input like a [`LogDisplayService`] with a prefix that may be replaced while the service is in use, returning the input as output.
+//! * [`DynamicPrefixLogDisplayService`] logs [`Display`] input at a configured log level to [`log::log`] after a prefix produced for each input, returning the input as output.
+//! * [`HeadThenSampleLogDisplayService`] logs the first inputs and then a sample of subsequent [`Display`] input at a configured log level to [`log::log`], returning the input as output.
+//! * [`WatchLogDisplayService`] logs [`Display`] input at a configured log level to [`log::log`] only when it equals a watched value, returning the input as output.
+//! * [`LogService`] logs input with a [`FormatStyle`] chosen at runtime at a configured log level to [`log::log`], returning the input as output.
+//! * [`HeartbeatLogService`] logs a heartbeat at a configured log level to [`log::log`] when an interval has elapsed since the last heartbeat, returning the input as output.
+//! * [`LogTimedService`] times an underlying service, logging the elapsed time at a configured log level to [`log::log`].
+//! * [`LogFieldsService`] logs key-value pairs extracted from input as structured fields at a configured log level to [`log::log`], returning the input as output. Requires the `kv` feature.
+//! * [`TeeLogDisplayService`] logs [`Display`] input at a configured log level to [`log::log`] and sends a clone of the input to a channel, returning the input as output.
+//! * [`LogUtf8LossyService`] logs byte input as lossy UTF-8 text at a configured log level to [`log::log`], returning the input as output.
+//! * [`LogSizeService`] logs the memory size of input at a configured log level to [`log::log`], returning the input as output.
+//! * [`LogPreviewService`] logs a bounded preview of [`Debug`] elements of [`Vec`] input at a configured log level to [`log::log`], returning the input as output.
+//! * [`LogNumberService`] logs integer input with grouped thousands at a configured log level to [`log::log`], returning the input as output.
+//! * [`RingBufferLogDisplayService`] logs [`Display`] input at a configured log level to [`log::log`] and retains the most recent log statements in memory, returning the input as output.
+//! * [`LogDedupService`] logs [`Display`] input at a configured log level to [`log::log`] unless an equal value was logged within a time window, returning the input as output.
+//! * [`LogDurationService`] logs [`Duration`] input, or a [`Duration`] extracted from input, in a compact human-readable form at a configured log level to [`log::log`], returning the input as output.
+//! * [`LogTransitionService`] logs [`Debug`] input at a configured log level to [`log::log`] only when it differs from the previous input, returning the input as output.
+//! * [`LogOrErrService`] logs [`Debug`] input at a configured log level to [`log::log`], returning the input as output, or a configured error when a predicate rejects the input.
+//! * [`LogWriter`] logs each line written to it at a configured log level to [`log::log`], implementing [`std::io::Write`].
+//! * [`LogVariantService`] logs the variant of enum input at a configured log level to [`log::log`], returning the input as output.
+//! * [`EscalatingLogDisplayService`] logs [`Display`] input at a base log level to [`log::log`], escalating to another log level when an equal value recurs more than a threshold number of times within a window, returning the input as output.
+//! * [`LogEscalateService`] logs [`Display`] input once at every log level up to a configured maximum level to [`log::log`], returning the input as output.
+//! * [`LogRateService`] counts input and logs the observed throughput at a configured log level to [`log::log`] when an interval has elapsed, returning the input as output.
+//! * [`LogPairedLevelService`] logs [`Display`] input at the log level it is paired with to [`log::log`], returning the input without the log level as output.
+//! * [`HistogramLogService`] records numeric input in a bucketed histogram and logs estimated percentiles at a configured log level to [`log::log`] after every configured number of inputs, returning the input as output.
+//! * [`LogTupleService`] logs the [`Display`] elements of tuple input with labels at a configured log level to [`log::log`], returning the input as output.
+//! * [`ThresholdCrossingLogService`] logs when input crosses a threshold at a configured log level to [`log::log`], returning the input as output.
+//! * [`LogSinkService`] passes formatted [`Display`] input with a configured log level to a user-provided sink function, returning the input as output.
+//! * [`OptionFillRateLogService`] counts how often optional input is `Some` and logs the fill rate at a configured log level to [`log::log`] after every window of inputs, returning the input as output.
+//! * [`LogOkFieldService`] logs a projection of the `Ok` value of [`Result`] input at a configured log level to [`log::log`], returning the input as output.
+//! * [`RenderLogService`] logs input rendered by a type-erased renderer at a configured log level to [`log::log`], returning the input as output.
+//! * [`LogCloneTeeService`] logs [`Display`] input at a configured log level to [`log::log`], returning the input and a clone of it as output.
+//! * [`LogDistinctErrorService`] logs the [`Display`] error of [`Result`] input at a configured log level to [`log::log`] when it differs from the last logged error, returning the input as output.
+//! * [`EpochLogDisplayService`] logs [`Display`] input tagged with a caller-incremented epoch number at a configured log level to [`log::log`], returning the input as output.
+//! * [`LogDisplayServiceShared`] logs [`Display`] input at a configured log level to [`log::log`], returning the input as output, and is `'static` and cheap to clone.
+//! * [`LogDeltaService`] logs the signed difference between numeric input and the previous input at a configured log level to [`log::log`], returning the input as output.
+//! * [`LogPaceService`] logs [`Display`] input at a configured log level to [`log::log`] and then sleeps for a configured delay to pace a service chain while debugging, returning the input as output. Requires the `debug-tools` feature.
+//! * [`DeferredLogDisplayService`] buffers [`Display`] input, logging it at a configured log level to [`log::log`] only when committed, returning the input as output.
+//! * [`LogFirstNService`] logs the first configured number of [`Display`] inputs at a configured log level to [`log::log`], returning the input as output.
+//! * [`CyclicIndexLogService`] logs the slot of each input within a repeating cycle at a configured log level to [`log::log`], returning the input as output.
+//! * [`LogIntoService`] logs [`Display`] input at a configured log level to [`log::log`], returning the input converted with [`From`] as output.
+//! * [`LogMapService`] transforms [`Display`] input with a function, logging both the input and the output at a configured log level to [`log::log`], returning the output.
+//! * [`SuppressibleLogService`] passes input to an underlying logging service, skipping values marked with [`Loggable::Skip`] or wrapped in [`NoLog`] without logging them, returning the unwrapped input as output.
+//! * [`AdaptiveAlertLogResultService`] logs the [`Display`] error of [`Result`] input at a configured log level to [`log::log`], additionally logging to an alert target while the recent error rate exceeds a threshold, returning the input as output.
+//! * [`LogLogfmtService`] logs [`Display`] input as a logfmt line at a configured log level to [`log::log`], returning the input as output.
 //!
 //! ## Use Case
 //! These [`Service`] impls are most useful for logging an event as it passes through a service chain.
 //!
+//! ## Ownership
+//! Every service takes input by value, formats it by reference, and returns the same value as output without cloning it.
+//! Moving input through a logging stage is therefore zero-copy, even for large values that are not [`Clone`].
+//! Only services that explicitly document it, such as [`LogTeeService`] and [`LogCloneTeeService`], clone their input.
+//!
+//! Formatting is allocation-free as well: the prefix and input are passed to [`log::log`] as [`format_args!`] rather than formatted into an intermediate [`String`].
+//! Options that transform the formatted input, such as `with_max_len`, `with_trim`, `with_width`, `with_split_lines`, or `with_single_line`, format it into a reused thread-local buffer instead,
+//! and only `with_case` and `with_capture` allocate per log statement.
+//!
+//! ## Prefixes
+//! Prefixes are always passed to [`log::log`] as an argument rather than as part of the format string,
+//! so a prefix containing format-like braces such as `{}` is logged verbatim.
+//! A process-global prefix, such as an application name, may be prepended to every subsequently constructed service with [`set_default_prefix`].
+//! Use `try_new` to reject prefixes from untrusted sources that contain ASCII control characters, such as newlines, which may be used for log injection,
+//! and `try_new_non_empty` to also reject empty or whitespace-only prefixes that produce anonymous log statements.
+//!
+//! ## Tracing
+//! Enabling the `tracing` feature emits every log statement as a [`tracing`](https://docs.rs/tracing) event at the matching level instead of calling [`log::log`],
+//! with the prefix and value recorded as the `prefix` and `value` fields.
+//! `tracing` requires a constant target, so the configured target is recorded as the `log.target` field instead, along with the location captured by [`log_display!`] as the `log.module_path`, `log.file`, and `log.line` fields.
+//! Statements fall back to stderr with `with_fallback_stderr` while no subscriber is set, and `with_flush_every` has no effect.
+//! The `log` backend remains the default.
+//!
 //! ## Example
 //! ```
 //! use sod::Service;
@@ -18,60 +93,320 @@
 
 use std::{
     borrow::Cow,
-    fmt::{Debug, Display},
+    cell::RefCell,
+    collections::{hash_map::DefaultHasher, HashMap, VecDeque},
+    fmt::{self, Arguments, Debug, Display},
+    hash::{BuildHasher, BuildHasherDefault, Hash},
+    io::{self, Write},
     marker::PhantomData,
+    num::{NonZeroU64, NonZeroUsize},
+    ops::Sub,
+    sync::{
+        atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering},
+        mpsc::Sender,
+        Arc, Mutex, PoisonError, RwLock,
+    },
+    time::{Duration, Instant},
 };
 
-use log::Level;
-use sod::Service;
+use log::{Level, LevelFilter, ParseLevelError};
+use sod::{FnService, NoOpService, Service, ServiceChain};
+
+pub mod prelude;
+#[cfg(test)]
+mod tests;
+
+/// Implement the constructors and builders shared by the services that implement `new` and store their [`LogConfig`] in a field named `config`
+macro_rules! impl_log_builders {
+    ($service:ident) => {
+        impl<'a, T> $service<'a, T> {
+            /// Log input at the given log level, rejecting a prefix that contains ASCII control characters
+            /// # Arguments
+            /// * `level` - The log level
+            /// * `prefix` - A prefix to prepend to the beginning of the log statement
+            pub fn try_new<S: Into<Cow<'a, str>>>(
+                level: Level,
+                prefix: S,
+            ) -> Result<Self, PrefixError> {
+                let prefix = prefix.into();
+                validate_prefix(&prefix)?;
+                Ok(Self::new(level, prefix))
+            }
+            /// Log input at the given log level, rejecting a prefix that is empty, only whitespace, or contains ASCII control characters
+            /// # Arguments
+            /// * `level` - The log level
+            /// * `prefix` - A prefix to prepend to the beginning of the log statement
+            pub fn try_new_non_empty<S: Into<Cow<'a, str>>>(
+                level: Level,
+                prefix: S,
+            ) -> Result<Self, PrefixError> {
+                let prefix = prefix.into();
+                if prefix.trim().is_empty() {
+                    return Err(PrefixError::Empty);
+                }
+                Self::try_new(level, prefix)
+            }
+            /// Log input at the level of the given filter, where [`LevelFilter::Off`] disables logging while keeping the same type
+            /// # Arguments
+            /// * `filter` - The log level filter
+            /// * `prefix` - A prefix to prepend to the beginning of the log statement
+            pub fn from_filter<S: Into<Cow<'a, str>>>(filter: LevelFilter, prefix: S) -> Self {
+                Self::new(Level::Info, prefix).with_filter(filter)
+            }
+            /// Log input at the level parsed from the given environment variable, such as `debug` or `off`, defaulting to [`Level::Info`] when it is not set
+            /// # Arguments
+            /// * `var` - The name of the environment variable
+            /// * `prefix` - A prefix to prepend to the beginning of the log statement
+            pub fn from_env<S: Into<Cow<'a, str>>>(
+                var: &str,
+                prefix: S,
+            ) -> Result<Self, ParseLevelError> {
+                Ok(Self::from_filter(filter_from_env(var)?, prefix))
+            }
+            /// Log as [`Level::Debug`]
+            /// # Arguments
+            /// * `prefix` - A prefix to prepend to the beginning of the log statement
+            pub fn debug<S: Into<Cow<'a, str>>>(prefix: S) -> Self {
+                Self::new(Level::Debug, prefix)
+            }
+            /// Log as [`Level::Error`]
+            /// # Arguments
+            /// * `prefix` - A prefix to prepend to the beginning of the log statement
+            pub fn error<S: Into<Cow<'a, str>>>(prefix: S) -> Self {
+                Self::new(Level::Error, prefix)
+            }
+            /// Log as [`Level::Info`]
+            /// # Arguments
+            /// * `prefix` - A prefix to prepend to the beginning of the log statement
+            pub fn info<S: Into<Cow<'a, str>>>(prefix: S) -> Self {
+                Self::new(Level::Info, prefix)
+            }
+            /// Log as [`Level::Trace`]
+            /// # Arguments
+            /// * `prefix` - A prefix to prepend to the beginning of the log statement
+            pub fn trace<S: Into<Cow<'a, str>>>(prefix: S) -> Self {
+                Self::new(Level::Trace, prefix)
+            }
+            /// Log as [`Level::Warn`]
+            /// # Arguments
+            /// * `prefix` - A prefix to prepend to the beginning of the log statement
+            pub fn warn<S: Into<Cow<'a, str>>>(prefix: S) -> Self {
+                Self::new(Level::Warn, prefix)
+            }
+            /// Replace the configured log level
+            /// # Arguments
+            /// * `level` - The log level
+            pub fn with_level(mut self, level: Level) -> Self {
+                self.config.set_filter(level.to_level_filter());
+                self
+            }
+            /// Replace the configured log level with the given filter, where [`LevelFilter::Off`] disables logging while keeping the service in the chain
+            /// # Arguments
+            /// * `filter` - The log level filter
+            pub fn with_filter(mut self, filter: LevelFilter) -> Self {
+                self.config.set_filter(filter);
+                self
+            }
+            /// Remap the level of each log statement before it is emitted, allowing one service definition to be reused with a different effective verbosity.
+            ///
+            /// The function is applied at emission time, so it also remaps a level replaced later with `with_level` or `with_filter`.
+            /// # Arguments
+            /// * `remap` - A function that transforms the configured level into the level that will be emitted
+            pub fn remap_level<F: Fn(Level) -> Level + Send + Sync + 'a>(
+                mut self,
+                remap: F,
+            ) -> Self {
+                self.config.remap = Some(Box::new(remap));
+                self
+            }
+            /// Fall back to writing to stderr with [`eprintln`] when no logger has been initialized, so diagnostics are not lost during early startup.
+            ///
+            /// A logger is considered uninitialized while [`log::max_level`] is [`LevelFilter::Off`], which is its value until a logger sets it.
+            /// With the `tracing` feature, it is considered uninitialized while no default subscriber is set.
+            pub fn with_fallback_stderr(mut self) -> Self {
+                self.config.fallback_stderr = true;
+                self
+            }
+            /// Cap the formatted input at the given number of characters, truncating it with a trailing `…` when exceeded.
+            ///
+            /// Formatting stops as soon as the cap is reached, which protects log backends from very large records.
+            /// # Arguments
+            /// * `max_len` - The maximum number of characters of formatted input to log
+            pub fn with_max_len(mut self, max_len: usize) -> Self {
+                self.config.max_len = Some(max_len);
+                self
+            }
+            /// Trim trailing whitespace and newlines from the formatted input, without affecting the prefix
+            pub fn with_trim(mut self) -> Self {
+                self.config.trim = true;
+                self
+            }
+            /// Pad the formatted input to at least the given number of characters, which aligns short values into columns, without affecting the prefix
+            /// # Arguments
+            /// * `width` - The minimum number of characters
+            pub fn with_width(mut self, width: usize) -> Self {
+                self.config.width = Some(width);
+                self
+            }
+            /// Set the alignment of input padded with `with_width`, which defaults to [`fmt::Alignment::Left`]
+            /// # Arguments
+            /// * `alignment` - The alignment
+            pub fn with_alignment(mut self, alignment: fmt::Alignment) -> Self {
+                self.config.alignment = alignment;
+                self
+            }
+            /// Change the case of the formatted input before logging it, without affecting the prefix or the returned input
+            /// # Arguments
+            /// * `case` - The case to log input as
+            pub fn with_case(mut self, case: LogCase) -> Self {
+                self.config.case = case;
+                self
+            }
+            /// Flush the logger with [`log::Log::flush`] after every `n` log statements, which is useful with asynchronous loggers
+            ///
+            /// This has no effect with the `tracing` feature, since subscribers cannot be flushed.
+            /// # Arguments
+            /// * `n` - The number of log statements between flushes, where `0` never flushes
+            pub fn with_flush_every(mut self, n: u64) -> Self {
+                self.config.flush_every = n;
+                self
+            }
+            /// Tag each log statement with the name of its level in the form of `[INFO] {prefix}{input}`, for sinks that do not show the level
+            pub fn with_level_tag(mut self) -> Self {
+                self.config.level_tag = true;
+                self
+            }
+            /// Tag each log statement with the current thread in the form of `[{thread}] {prefix}{input}`, using the thread name if it is set and its [`ThreadId`](std::thread::ThreadId) otherwise
+            pub fn with_thread_info(mut self) -> Self {
+                self.config.thread_info = true;
+                self
+            }
+            /// Tag each log statement with the syslog severity of the log level in the form of `<{severity}>{prefix}{input}`, as returned by [`syslog_severity`]
+            pub fn with_syslog_severity(mut self) -> Self {
+                self.config.syslog_severity = true;
+                self
+            }
+            /// Tag each log statement with the process-unique id of this service in the form of `#{id} {prefix}{input}`, which distinguishes the log statements of identical services
+            pub fn with_id(mut self) -> Self {
+                self.config.show_id = true;
+                self
+            }
+            /// Attach context fields, such as the service name or version, to each log statement.
+            ///
+            /// The fields are rendered in the form of `[{key}={value} {key}={value}] {prefix}{input}`, or attached to the [`log::Record`] as key-values when the `kv` feature is enabled.
+            /// They are still rendered when there is no record to attach them to, such as the stderr fallback and the message retained by `with_capture`.
+            /// # Arguments
+            /// * `context` - The key-value pairs to attach
+            pub fn with_context(mut self, context: Vec<(&'static str, String)>) -> Self {
+                self.config.context = context;
+                self
+            }
+            /// Get the process-unique id assigned to this service when it was constructed
+            pub fn id(&self) -> u64 {
+                self.config.id
+            }
+            /// Omit the process-global default prefix set by [`set_default_prefix`] from each log statement
+            pub fn without_default_prefix(mut self) -> Self {
+                self.config.default_prefix = String::new();
+                self
+            }
+            /// Retain the most recent log statement, which is read with `last_message` to assert on logging without installing a logger
+            pub fn with_capture(mut self) -> Self {
+                self.config.captured = Some(Mutex::new(None));
+                self
+            }
+            /// Get the most recent log statement, or `None` if nothing has been logged or capturing is not enabled with `with_capture`
+            pub fn last_message(&self) -> Option<String> {
+                self.config.last_message()
+            }
+            /// Log each line of the formatted input as its own log statement, each with the prefix.
+            ///
+            /// This replaces [`with_strip_newlines`](Self::with_strip_newlines).
+            pub fn with_split_lines(mut self) -> Self {
+                self.config.newlines = Newlines::Split;
+                self
+            }
+            /// Replace the newlines of the formatted input with spaces, so it is logged as a single line.
+            ///
+            /// This replaces [`with_split_lines`](Self::with_split_lines).
+            pub fn with_strip_newlines(mut self) -> Self {
+                self.config.newlines = Newlines::Strip;
+                self
+            }
+            /// Use a different prefix when logging at the given level, falling back to the default prefix for all other levels
+            /// # Arguments
+            /// * `level` - The log level
+            /// * `prefix` - A prefix to prepend to the beginning of the log statement when logging at `level`
+            pub fn prefix_for<S: Into<Cow<'a, str>>>(mut self, level: Level, prefix: S) -> Self {
+                self.config.level_prefixes.insert(level, prefix.into());
+                self
+            }
+            /// Log to the given target instead of the default target of `sod_log`
+            /// # Arguments
+            /// * `target` - The log target, such as a module path
+            pub fn with_target<S: Into<Cow<'a, str>>>(mut self, target: S) -> Self {
+                self.config.target = Some(target.into());
+                self
+            }
+            /// Log the given message at the configured level when the service is dropped, which is useful for tracing the teardown of a service chain
+            /// # Arguments
+            /// * `message` - The message to log when dropped
+            pub fn log_on_drop<S: Into<Cow<'a, str>>>(mut self, message: S) -> Self {
+                self.config.drop_message = Some(message.into());
+                self
+            }
+            /// Check if the configured level is enabled, which allows callers to skip building expensive input that would not be logged
+            pub fn is_enabled(&self) -> bool {
+                self.config.is_enabled()
+            }
+            /// Get the configured log level
+            pub fn level(&self) -> Level {
+                self.config.level
+            }
+            /// Get the configured prefix
+            pub fn prefix(&self) -> &str {
+                &self.config.prefix
+            }
+        }
+    };
+}
 
 /// A [`sod::Service`] that logs [`Debug`] input at a configured log level to [`log::log`], returning the input as output.
 pub struct LogDebugService<'a, T> {
-    level: Level,
-    prefix: Cow<'a, str>,
+    config: LogConfig<'a>,
     _phantom: PhantomData<fn(T)>,
 }
 impl<'a, T> LogDebugService<'a, T> {
     /// Log input at the given log level
     /// # Arguments
     /// * `level` - The log level
-    /// * `prefix` - A prefix to prepend to the beginning of the log statment
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
     pub fn new<S: Into<Cow<'a, str>>>(level: Level, prefix: S) -> Self {
         Self {
-            level,
-            prefix: prefix.into(),
+            config: LogConfig::new(level, prefix.into()),
             _phantom: PhantomData,
         }
     }
-    /// Log as [`Level::Debug`]
-    /// # Arguments
-    /// * `prefix` - A prefix to prepend to the beginning of the log statment
-    pub fn debug<S: Into<Cow<'a, str>>>(prefix: S) -> Self {
-        Self::new(Level::Debug, prefix)
-    }
-    /// Log as [`Level::Error`]
-    /// # Arguments
-    /// * `prefix` - A prefix to prepend to the beginning of the log statment
-    pub fn error<S: Into<Cow<'a, str>>>(prefix: S) -> Self {
-        Self::new(Level::Error, prefix)
-    }
-    /// Log as [`Level::Info`]
-    /// # Arguments
-    /// * `prefix` - A prefix to prepend to the beginning of the log statment
-    pub fn info<S: Into<Cow<'a, str>>>(prefix: S) -> Self {
-        Self::new(Level::Info, prefix)
-    }
-    /// Log as [`Level::Trace`]
-    /// # Arguments
-    /// * `prefix` - A prefix to prepend to the beginning of the log statment
-    pub fn trace<S: Into<Cow<'a, str>>>(prefix: S) -> Self {
-        Self::new(Level::Trace, prefix)
+    /// Replace the newlines of the formatted input with spaces and collapse runs of whitespace, so a multi-line [`Debug`] impl is logged as a compact single line.
+    ///
+    /// This replaces [`with_split_lines`](Self::with_split_lines) and [`with_strip_newlines`](Self::with_strip_newlines).
+    pub fn with_single_line(mut self) -> Self {
+        self.config.newlines = Newlines::Collapse;
+        self
     }
-    /// Log as [`Level::Warn`]
+}
+impl_log_builders!(LogDebugService);
+impl<'a, T: Debug> LogDebugService<'a, T> {
+    /// Build a [`ServiceChain`] that logs input with this service and then transforms it with the given function
     /// # Arguments
-    /// * `prefix` - A prefix to prepend to the beginning of the log statment
-    pub fn warn<S: Into<Cow<'a, str>>>(prefix: S) -> Self {
-        Self::new(Level::Warn, prefix)
+    /// * `map` - The function that transforms the logged input
+    pub fn then_map<O, F: Fn(T) -> O>(
+        self,
+        map: F,
+    ) -> LogThenMapService<'a, Self, O, impl Fn(T) -> Result<O, ()>> {
+        ServiceChain::start(self)
+            .next(FnService::new(move |input| Ok(map(input))))
+            .end()
     }
 }
 impl<'a, T: Debug> Service for LogDebugService<'a, T> {
@@ -79,60 +414,71 @@ impl<'a, T: Debug> Service for LogDebugService<'a, T> {
     type Output = T;
     type Error = ();
     fn process(&self, input: T) -> Result<Self::Output, Self::Error> {
-        log::log!(self.level, "{}{:?}", self.prefix, input);
+        self.config.log(format_args!("{:?}", input));
         Ok(input)
     }
 }
+impl<'a, T> Display for LogDebugService<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.config
+            .fmt_summary(f, "LogDebugService", &self.config.prefix)
+    }
+}
 
 /// A [`sod::Service`] that logs optional [`Debug`] input when it is `Some(input)` at a configured log level to [`log::log`], returning the input as output.
 ///
 /// This service is useful for logging an event as it passed through a service chain, while ignoring non-blocking service chains that may continuously process `None` in a tight loop.
 pub struct LogOptionalDebugService<'a, T> {
-    level: Level,
-    prefix: Cow<'a, str>,
+    config: LogConfig<'a>,
+    on_none: Option<Box<dyn Fn() + Send + Sync + 'a>>,
+    required: bool,
     _phantom: PhantomData<fn(T)>,
 }
 impl<'a, T> LogOptionalDebugService<'a, T> {
     /// Log input at the given log level
     /// # Arguments
     /// * `level` - The log level
-    /// * `prefix` - A prefix to prepend to the beginning of the log statment
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
     pub fn new<S: Into<Cow<'a, str>>>(level: Level, prefix: S) -> Self {
         Self {
-            level,
-            prefix: prefix.into(),
+            config: LogConfig::new(level, prefix.into()),
+            on_none: None,
+            required: false,
             _phantom: PhantomData,
         }
     }
-    /// Log as [`Level::Debug`]
-    /// # Arguments
-    /// * `prefix` - A prefix to prepend to the beginning of the log statment
-    pub fn debug<S: Into<Cow<'a, str>>>(prefix: S) -> Self {
-        Self::new(Level::Debug, prefix)
-    }
-    /// Log as [`Level::Error`]
-    /// # Arguments
-    /// * `prefix` - A prefix to prepend to the beginning of the log statment
-    pub fn error<S: Into<Cow<'a, str>>>(prefix: S) -> Self {
-        Self::new(Level::Error, prefix)
+    /// Replace the newlines of the formatted input with spaces and collapse runs of whitespace, so a multi-line [`Debug`] impl is logged as a compact single line.
+    ///
+    /// This replaces [`with_split_lines`](Self::with_split_lines) and [`with_strip_newlines`](Self::with_strip_newlines).
+    pub fn with_single_line(mut self) -> Self {
+        self.config.newlines = Newlines::Collapse;
+        self
     }
-    /// Log as [`Level::Info`]
+    /// Invoke the given function each time `None` is processed, which allows side effects such as metrics without logging
     /// # Arguments
-    /// * `prefix` - A prefix to prepend to the beginning of the log statment
-    pub fn info<S: Into<Cow<'a, str>>>(prefix: S) -> Self {
-        Self::new(Level::Info, prefix)
+    /// * `on_none` - The function to invoke
+    pub fn with_on_none<F: Fn() + Send + Sync + 'a>(mut self, on_none: F) -> Self {
+        self.on_none = Some(Box::new(on_none));
+        self
     }
-    /// Log as [`Level::Trace`]
-    /// # Arguments
-    /// * `prefix` - A prefix to prepend to the beginning of the log statment
-    pub fn trace<S: Into<Cow<'a, str>>>(prefix: S) -> Self {
-        Self::new(Level::Trace, prefix)
+    /// Return `Err(())` and log a warning when `None` is processed, instead of returning `Ok(None)`, for downstream services that require a value
+    pub fn with_required(mut self) -> Self {
+        self.required = true;
+        self
     }
-    /// Log as [`Level::Warn`]
+}
+impl_log_builders!(LogOptionalDebugService);
+impl<'a, T: Debug> LogOptionalDebugService<'a, T> {
+    /// Build a [`ServiceChain`] that logs input with this service and then transforms it with the given function
     /// # Arguments
-    /// * `prefix` - A prefix to prepend to the beginning of the log statment
-    pub fn warn<S: Into<Cow<'a, str>>>(prefix: S) -> Self {
-        Self::new(Level::Warn, prefix)
+    /// * `map` - The function that transforms the logged input
+    pub fn then_map<O, F: Fn(Option<T>) -> O>(
+        self,
+        map: F,
+    ) -> LogThenMapService<'a, Self, O, impl Fn(Option<T>) -> Result<O, ()>> {
+        ServiceChain::start(self)
+            .next(FnService::new(move |input| Ok(map(input))))
+            .end()
     }
 }
 impl<'a, T: Debug> Service for LogOptionalDebugService<'a, T> {
@@ -140,133 +486,5163 @@ impl<'a, T: Debug> Service for LogOptionalDebugService<'a, T> {
     type Output = Option<T>;
     type Error = ();
     fn process(&self, input: Option<T>) -> Result<Self::Output, Self::Error> {
-        if let Some(input) = &input {
-            log::log!(self.level, "{}{:?}", self.prefix, input);
+        match &input {
+            Some(input) => self.config.log(format_args!("{:?}", input)),
+            None => {
+                if let Some(on_none) = &self.on_none {
+                    on_none();
+                }
+                if self.required {
+                    self.config.warn(format_args!("required input was None"));
+                    return Err(());
+                }
+            }
         }
         Ok(input)
     }
 }
+impl<'a, T> Display for LogOptionalDebugService<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.config
+            .fmt_summary(f, "LogOptionalDebugService", &self.config.prefix)
+    }
+}
 
 /// A [`sod::Service`] that logs [`Display`] input at a configured log level to [`log::log`], returning the input as output.
 ///
 /// This service is useful for logging an event as it passed through a service chain.
 pub struct LogDisplayService<'a, T> {
-    level: Level,
-    prefix: Cow<'a, str>,
+    config: LogConfig<'a>,
+    sequence: Option<AtomicU64>,
+    counter: Option<Arc<AtomicU64>>,
     _phantom: PhantomData<fn(T)>,
 }
 impl<'a, T> LogDisplayService<'a, T> {
     /// Log input at the given log level
     /// # Arguments
     /// * `level` - The log level
-    /// * `prefix` - A prefix to prepend to the beginning of the log statment
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
     pub fn new<S: Into<Cow<'a, str>>>(level: Level, prefix: S) -> Self {
         Self {
-            level,
-            prefix: prefix.into(),
+            config: LogConfig::new(level, prefix.into()),
+            sequence: None,
+            counter: None,
+            _phantom: PhantomData,
+        }
+    }
+    /// Log input at the given log level with a [`DynamicPrefixLogDisplayService`], which produces the prefix with the given function each time input is logged
+    /// # Arguments
+    /// * `level` - The log level
+    /// * `prefix` - A function that produces the prefix to prepend to the beginning of each log statement
+    pub fn from_prefix_fn<P: AsRef<str>, F: Fn() -> P>(
+        level: Level,
+        prefix: F,
+    ) -> DynamicPrefixLogDisplayService<'a, T, F> {
+        DynamicPrefixLogDisplayService::new(level, prefix)
+    }
+    /// Tag each log statement with a sequence number in the form of `{prefix}#{sequence} {input}`.
+    ///
+    /// The sequence starts at `#0` for the first log statement and increments by one for each processed input.
+    pub fn with_sequence(mut self) -> Self {
+        self.sequence = Some(AtomicU64::new(0));
+        self
+    }
+    /// Attribute log statements to the given source location instead of this crate, which also becomes the target unless one is set with `with_target`.
+    ///
+    /// This is typically called by [`log_display!`] to capture the location of the caller. The `tracing` backend records the location as the `log.module_path`, `log.file`, and `log.line` fields.
+    /// # Arguments
+    /// * `module_path` - The module path of the caller, as returned by [`module_path!`]
+    /// * `file` - The file of the caller, as returned by [`file!`]
+    /// * `line` - The line of the caller, as returned by [`line!`]
+    pub fn with_location(
+        mut self,
+        module_path: &'static str,
+        file: &'static str,
+        line: u32,
+    ) -> Self {
+        self.config.location = Some(Location {
+            module_path,
+            file,
+            line,
+        });
+        self
+    }
+    /// Increment the given counter each time input is processed, including when the log level is disabled, which allows quick instrumentation alongside logging
+    /// # Arguments
+    /// * `counter` - The counter to increment, which is shared with the caller to read the total
+    pub fn with_counter(mut self, counter: Arc<AtomicU64>) -> Self {
+        self.counter = Some(counter);
+        self
+    }
+    /// Get the counter configured with [`LogDisplayService::with_counter`]
+    pub fn counter(&self) -> Option<&Arc<AtomicU64>> {
+        self.counter.as_ref()
+    }
+    /// Allow the prefix to be replaced while the service is in use, which allows a single service to be reused across phases.
+    ///
+    /// The prefix of the returned service is guarded by a lock, so the prefix of a `LogDisplayService` is never locked.
+    pub fn with_swappable_prefix(self) -> SwappablePrefixLogDisplayService<'a, T> {
+        SwappablePrefixLogDisplayService {
+            prefix: RwLock::new(Arc::from(self.config.prefix.as_ref())),
+            service: self,
+        }
+    }
+    /// Increment the counter, if any, and get the sequence number of the input being processed, if enabled
+    fn count(&self) -> Option<u64> {
+        if let Some(counter) = &self.counter {
+            counter.fetch_add(1, Ordering::Relaxed);
+        }
+        self.sequence
+            .as_ref()
+            .map(|sequence| sequence.fetch_add(1, Ordering::Relaxed))
+    }
+}
+impl_log_builders!(LogDisplayService);
+impl<'a, T: Display> LogDisplayService<'a, T> {
+    /// Build a [`ServiceChain`] that logs input with this service and then transforms it with the given function
+    /// ```
+    /// use sod::Service;
+    /// use sod_log::LogDisplayService;
+    ///
+    /// let chain = LogDisplayService::info("input: ").then_map(|input: u32| input * 2);
+    /// assert_eq!(chain.process(21).unwrap(), 42);
+    /// ```
+    /// # Arguments
+    /// * `map` - The function that transforms the logged input
+    pub fn then_map<O, F: Fn(T) -> O>(
+        self,
+        map: F,
+    ) -> LogThenMapService<'a, Self, O, impl Fn(T) -> Result<O, ()>> {
+        ServiceChain::start(self)
+            .next(FnService::new(move |input| Ok(map(input))))
+            .end()
+    }
+    /// Log input with the given sequence number after the given prefix, unless a prefix is configured for the level
+    fn log_with(&self, sequence: Option<u64>, prefix: &str, input: &T) {
+        let prefix = self
+            .config
+            .level_prefixes
+            .get(&self.config.level)
+            .map_or(prefix, |prefix| prefix);
+        match sequence {
+            Some(sequence) => self
+                .config
+                .log_prefixed(prefix, format_args!("#{} {}", sequence, input)),
+            None => self.config.log_prefixed(prefix, format_args!("{}", input)),
+        }
+    }
+}
+impl<'a, T: Display> Service for LogDisplayService<'a, T> {
+    type Input = T;
+    type Output = T;
+    type Error = ();
+    fn process(&self, input: T) -> Result<Self::Output, Self::Error> {
+        let sequence = self.count();
+        self.log_with(sequence, &self.config.prefix, &input);
+        Ok(input)
+    }
+}
+impl<'a, T> Display for LogDisplayService<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.config
+            .fmt_summary(f, "LogDisplayService", &self.config.prefix)
+    }
+}
+
+/// A [`sod::Service`] that logs [`Display`] input like a [`LogDisplayService`], with a prefix that may be replaced while the service is in use, returning the input as output.
+///
+/// This service is built with [`LogDisplayService::with_swappable_prefix`], and allows a single service to be reused across phases.
+/// Because the prefix may be replaced while input is being processed, it is guarded by a [`RwLock`], which costs an uncontended read lock per log statement.
+/// The lock is only taken when the log statement will be emitted, and is released before input is formatted and logged,
+/// so the prefix may be replaced from the [`Display`] implementation of the input or from the logger.
+/// ```
+/// use sod::Service;
+/// use sod_log::LogDisplayService;
+///
+/// let logging_service = LogDisplayService::info("phase 1: ").with_swappable_prefix();
+/// logging_service.process("hello world!").unwrap();
+/// logging_service.set_prefix("phase 2: ");
+/// logging_service.process("hello world!").unwrap();
+/// ```
+pub struct SwappablePrefixLogDisplayService<'a, T> {
+    service: LogDisplayService<'a, T>,
+    prefix: RwLock<Arc<str>>,
+}
+impl<'a, T> SwappablePrefixLogDisplayService<'a, T> {
+    /// Replace the prefix of subsequent log statements
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    pub fn set_prefix<S: Into<Arc<str>>>(&self, prefix: S) {
+        *self.prefix.write().unwrap_or_else(PoisonError::into_inner) = prefix.into();
+    }
+    /// Get the current prefix, which is shared rather than borrowed, since it may be replaced at any time
+    pub fn prefix(&self) -> Arc<str> {
+        Arc::clone(&self.prefix.read().unwrap_or_else(PoisonError::into_inner))
+    }
+}
+impl<'a, T: Display> Service for SwappablePrefixLogDisplayService<'a, T> {
+    type Input = T;
+    type Output = T;
+    type Error = ();
+    fn process(&self, input: T) -> Result<Self::Output, Self::Error> {
+        let sequence = self.service.count();
+        if self.service.config.will_emit() {
+            self.service.log_with(sequence, &self.prefix(), &input);
+        }
+        Ok(input)
+    }
+}
+impl<'a, T> Display for SwappablePrefixLogDisplayService<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.service
+            .config
+            .fmt_summary(f, "SwappablePrefixLogDisplayService", &self.prefix())
+    }
+}
+
+/// A [`sod::Service`] that logs optional [`Display`] input when it is `Some(input)` at a configured log level to [`log::log`], returning the input as output.
+///
+/// This service is useful for logging an event as it passed through a service chain, while ignoring non-blocking service chains that may continuously process `None` in a tight loop.
+pub struct LogOptionalDisplayService<'a, T> {
+    config: LogConfig<'a>,
+    on_none: Option<Box<dyn Fn() + Send + Sync + 'a>>,
+    required: bool,
+    _phantom: PhantomData<fn(T)>,
+}
+impl<'a, T> LogOptionalDisplayService<'a, T> {
+    /// Log input at the given log level
+    /// # Arguments
+    /// * `level` - The log level
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    pub fn new<S: Into<Cow<'a, str>>>(level: Level, prefix: S) -> Self {
+        Self {
+            config: LogConfig::new(level, prefix.into()),
+            on_none: None,
+            required: false,
+            _phantom: PhantomData,
+        }
+    }
+    /// Invoke the given function each time `None` is processed, which allows side effects such as metrics without logging
+    /// # Arguments
+    /// * `on_none` - The function to invoke
+    pub fn with_on_none<F: Fn() + Send + Sync + 'a>(mut self, on_none: F) -> Self {
+        self.on_none = Some(Box::new(on_none));
+        self
+    }
+    /// Return `Err(())` and log a warning when `None` is processed, instead of returning `Ok(None)`, for downstream services that require a value
+    pub fn with_required(mut self) -> Self {
+        self.required = true;
+        self
+    }
+}
+impl_log_builders!(LogOptionalDisplayService);
+impl<'a, T: Display> LogOptionalDisplayService<'a, T> {
+    /// Build a [`ServiceChain`] that logs input with this service and then transforms it with the given function
+    /// # Arguments
+    /// * `map` - The function that transforms the logged input
+    pub fn then_map<O, F: Fn(Option<T>) -> O>(
+        self,
+        map: F,
+    ) -> LogThenMapService<'a, Self, O, impl Fn(Option<T>) -> Result<O, ()>> {
+        ServiceChain::start(self)
+            .next(FnService::new(move |input| Ok(map(input))))
+            .end()
+    }
+}
+impl<'a, T: Display> Service for LogOptionalDisplayService<'a, T> {
+    type Input = Option<T>;
+    type Output = Option<T>;
+    type Error = ();
+    fn process(&self, input: Option<T>) -> Result<Self::Output, Self::Error> {
+        match &input {
+            Some(input) => self.config.log(format_args!("{}", input)),
+            None => {
+                if let Some(on_none) = &self.on_none {
+                    on_none();
+                }
+                if self.required {
+                    self.config.warn(format_args!("required input was None"));
+                    return Err(());
+                }
+            }
+        }
+        Ok(input)
+    }
+}
+impl<'a, T> Display for LogOptionalDisplayService<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.config
+            .fmt_summary(f, "LogOptionalDisplayService", &self.config.prefix)
+    }
+}
+
+/// A [`sod::Service`] that logs the hash of [`Hash`] input at a configured log level to [`log::log`], returning the input as output.
+///
+/// This service is useful for correlating large payloads across systems without logging the full payload.
+/// The hash is computed with [`DefaultHasher`] unless a different [`BuildHasher`] is provided with [`LogHashService::with_hasher`].
+pub struct LogHashService<'a, T, H = BuildHasherDefault<DefaultHasher>> {
+    config: LogConfig<'a>,
+    hasher: H,
+    _phantom: PhantomData<fn(T)>,
+}
+impl<'a, T> LogHashService<'a, T> {
+    /// Log input at the given log level
+    /// # Arguments
+    /// * `level` - The log level
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    pub fn new<S: Into<Cow<'a, str>>>(level: Level, prefix: S) -> Self {
+        Self {
+            config: LogConfig::new(level, prefix.into()),
+            hasher: BuildHasherDefault::default(),
             _phantom: PhantomData,
         }
     }
     /// Log as [`Level::Debug`]
     /// # Arguments
-    /// * `prefix` - A prefix to prepend to the beginning of the log statment
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
     pub fn debug<S: Into<Cow<'a, str>>>(prefix: S) -> Self {
         Self::new(Level::Debug, prefix)
     }
     /// Log as [`Level::Error`]
     /// # Arguments
-    /// * `prefix` - A prefix to prepend to the beginning of the log statment
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
     pub fn error<S: Into<Cow<'a, str>>>(prefix: S) -> Self {
         Self::new(Level::Error, prefix)
     }
     /// Log as [`Level::Info`]
     /// # Arguments
-    /// * `prefix` - A prefix to prepend to the beginning of the log statment
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
     pub fn info<S: Into<Cow<'a, str>>>(prefix: S) -> Self {
         Self::new(Level::Info, prefix)
     }
     /// Log as [`Level::Trace`]
     /// # Arguments
-    /// * `prefix` - A prefix to prepend to the beginning of the log statment
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
     pub fn trace<S: Into<Cow<'a, str>>>(prefix: S) -> Self {
         Self::new(Level::Trace, prefix)
     }
     /// Log as [`Level::Warn`]
     /// # Arguments
-    /// * `prefix` - A prefix to prepend to the beginning of the log statment
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
     pub fn warn<S: Into<Cow<'a, str>>>(prefix: S) -> Self {
         Self::new(Level::Warn, prefix)
     }
 }
-impl<'a, T: Display> Service for LogDisplayService<'a, T> {
+impl<'a, T, H> LogHashService<'a, T, H> {
+    /// Use the given [`BuildHasher`] to hash input, which allows a deterministic hasher to be used across processes
+    /// # Arguments
+    /// * `hasher` - The hasher builder
+    pub fn with_hasher<NH: BuildHasher>(self, hasher: NH) -> LogHashService<'a, T, NH> {
+        LogHashService {
+            config: self.config,
+            hasher,
+            _phantom: PhantomData,
+        }
+    }
+    /// Log to the given target instead of the default target of `sod_log`
+    /// # Arguments
+    /// * `target` - The log target, such as a module path
+    pub fn with_target<S: Into<Cow<'a, str>>>(mut self, target: S) -> Self {
+        self.config.target = Some(target.into());
+        self
+    }
+    /// Check if the configured level is enabled, which allows callers to skip building expensive input that would not be logged
+    pub fn is_enabled(&self) -> bool {
+        self.config.is_enabled()
+    }
+}
+impl<'a, T: Hash, H: BuildHasher> Service for LogHashService<'a, T, H> {
     type Input = T;
     type Output = T;
     type Error = ();
     fn process(&self, input: T) -> Result<Self::Output, Self::Error> {
-        log::log!(self.level, "{}{}", self.prefix, input);
+        self.config
+            .log(format_args!("{:016x}", self.hasher.hash_one(&input)));
         Ok(input)
     }
 }
 
-/// A [`sod::Service`] that logs optional [`Display`] input when it is `Some(input)` at a configured log level to [`log::log`], returning the input as output.
+/// A [`sod::Service`] that passes a clone of [`Clone`] input to two underlying logging services, returning the original input as output.
 ///
-/// This service is useful for logging an event as it passed through a service chain, while ignoring non-blocking service chains that may continuously process `None` in a tight loop.
-pub struct LogOptionalDisplayService<'a, T> {
-    level: Level,
-    prefix: Cow<'a, str>,
+/// This service is useful for logging the same event at two different levels or with two different configurations in a single stage.
+/// The outputs of the underlying services are discarded, and the first error encountered is returned.
+pub struct LogTeeService<A, B> {
+    first: A,
+    second: B,
+}
+impl<A, B> LogTeeService<A, B> {
+    /// Tee input to both of the given services
+    /// # Arguments
+    /// * `first` - The first service to process the input
+    /// * `second` - The second service to process the input
+    pub fn new(first: A, second: B) -> Self {
+        Self { first, second }
+    }
+}
+impl<A: Service, B: Service<Input = A::Input, Error = A::Error>> Service for LogTeeService<A, B>
+where
+    A::Input: Clone,
+{
+    type Input = A::Input;
+    type Output = A::Input;
+    type Error = A::Error;
+    fn process(&self, input: Self::Input) -> Result<Self::Output, Self::Error> {
+        self.first.process(input.clone())?;
+        self.second.process(input.clone())?;
+        Ok(input)
+    }
+}
+
+/// A [`sod::Service`] that wraps a [`LogDisplayService`], counting the number of times input was logged at each level.
+///
+/// This service is useful for self-monitoring and for making assertions about logging in tests.
+/// Only log statements that are emitted are counted, so input processed while the level is disabled or filtered off is not counted.
+pub struct CountingByLevelLogService<'a, T> {
+    inner: LogDisplayService<'a, T>,
+    counts: [AtomicU64; 5],
+}
+impl<'a, T> CountingByLevelLogService<'a, T> {
+    /// Count the log statements of the given service
+    /// # Arguments
+    /// * `inner` - The underlying log service
+    pub fn new(inner: LogDisplayService<'a, T>) -> Self {
+        Self {
+            inner,
+            counts: Default::default(),
+        }
+    }
+    /// Get a snapshot of the number of log statements per level
+    pub fn counts(&self) -> LevelCounts {
+        let count = |level: Level| self.counts[level as usize - 1].load(Ordering::Relaxed);
+        LevelCounts {
+            error: count(Level::Error),
+            warn: count(Level::Warn),
+            info: count(Level::Info),
+            debug: count(Level::Debug),
+            trace: count(Level::Trace),
+        }
+    }
+}
+impl<'a, T: Display> Service for CountingByLevelLogService<'a, T> {
+    type Input = T;
+    type Output = T;
+    type Error = ();
+    fn process(&self, input: T) -> Result<Self::Output, Self::Error> {
+        if self.inner.config.will_emit() {
+            self.counts[self.inner.config.level as usize - 1].fetch_add(1, Ordering::Relaxed);
+        }
+        self.inner.process(input)
+    }
+}
+
+/// The number of log statements per level, as returned by [`CountingByLevelLogService::counts`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LevelCounts {
+    pub error: u64,
+    pub warn: u64,
+    pub info: u64,
+    pub debug: u64,
+    pub trace: u64,
+}
+
+/// Construct a log service that logs to the caller's module path as its target.
+///
+/// The service defaults to [`LogDisplayService`], and any of the other log services may be given as the first argument.
+/// ```
+/// use sod::Service;
+///
+/// let display_service = sod_log::log_here!(info, "event: ");
+/// display_service.process("hello world!").unwrap();
+///
+/// let debug_service = sod_log::log_here!(LogDebugService, debug, "event: ");
+/// debug_service.process("hello world!").unwrap();
+/// ```
+#[macro_export]
+macro_rules! log_here {
+    ($service:ident, $level:ident, $prefix:expr) => {
+        $crate::$service::$level($prefix).with_target(module_path!())
+    };
+    ($level:ident, $prefix:expr) => {
+        $crate::log_here!(LogDisplayService, $level, $prefix)
+    };
+}
+
+/// Construct a [`LogDisplayService`] that attributes its log statements to the caller's module path, file, and line.
+///
+/// The emitted [`log::Record`] reflects the caller's code rather than this crate, and its target is the caller's module path.
+/// ```
+/// use sod::Service;
+///
+/// let display_service = sod_log::log_display!(info, "event: ");
+/// display_service.process("hello world!").unwrap();
+/// ```
+#[macro_export]
+macro_rules! log_display {
+    ($level:ident, $prefix:expr) => {
+        $crate::LogDisplayService::$level($prefix).with_location(module_path!(), file!(), line!())
+    };
+}
+
+/// Build a [`ServiceChain`] from the given services, logging the [`Debug`] input of every service after the first with a [`LogDebugService`] at the given level.
+///
+/// Each intermediate value is logged in the form of `into {service}: {input}`, where `{service}` is the expression of the service that receives it.
+/// This macro is a debugging aid for inspecting every stage of a service chain.
+/// ```
+/// use sod::{FnService, Service};
+///
+/// let chain = sod_log::log_chain!(
+///     debug,
+///     FnService::new(|x: u32| Ok::<_, ()>(x + 1)),
+///     FnService::new(|x: u32| Ok::<_, ()>(x * 2))
+/// );
+/// assert_eq!(chain.process(1).unwrap(), 4);
+/// ```
+#[macro_export]
+macro_rules! log_chain {
+    ($level:ident, $first:expr $(, $service:expr)+ $(,)?) => {
+        $crate::__private::ServiceChain::start($first)
+            $(
+                .next($crate::LogDebugService::$level(concat!("into ", stringify!($service), ": ")))
+                .next($service)
+            )+
+            .end()
+    };
+}
+
+#[doc(hidden)]
+pub mod __private {
+    pub use sod::ServiceChain;
+}
+
+/// A [`sod::Service`] that logs each element of [`Vec`] input with its index at a configured log level to [`log::log`], returning the input as output.
+///
+/// Each element is logged as its own log statement in the form of `{prefix}[{index}] {element}`.
+pub struct LogEachDisplayService<'a, T> {
+    config: LogConfig<'a>,
+    max_elements: Option<usize>,
     _phantom: PhantomData<fn(T)>,
 }
-impl<'a, T> LogOptionalDisplayService<'a, T> {
+impl<'a, T> LogEachDisplayService<'a, T> {
     /// Log input at the given log level
     /// # Arguments
     /// * `level` - The log level
-    /// * `prefix` - A prefix to prepend to the beginning of the log statment
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
     pub fn new<S: Into<Cow<'a, str>>>(level: Level, prefix: S) -> Self {
         Self {
-            level,
-            prefix: prefix.into(),
+            config: LogConfig::new(level, prefix.into()),
+            max_elements: None,
             _phantom: PhantomData,
         }
     }
     /// Log as [`Level::Debug`]
     /// # Arguments
-    /// * `prefix` - A prefix to prepend to the beginning of the log statment
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
     pub fn debug<S: Into<Cow<'a, str>>>(prefix: S) -> Self {
         Self::new(Level::Debug, prefix)
     }
     /// Log as [`Level::Error`]
     /// # Arguments
-    /// * `prefix` - A prefix to prepend to the beginning of the log statment
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
     pub fn error<S: Into<Cow<'a, str>>>(prefix: S) -> Self {
         Self::new(Level::Error, prefix)
     }
     /// Log as [`Level::Info`]
     /// # Arguments
-    /// * `prefix` - A prefix to prepend to the beginning of the log statment
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
     pub fn info<S: Into<Cow<'a, str>>>(prefix: S) -> Self {
         Self::new(Level::Info, prefix)
     }
     /// Log as [`Level::Trace`]
     /// # Arguments
-    /// * `prefix` - A prefix to prepend to the beginning of the log statment
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
     pub fn trace<S: Into<Cow<'a, str>>>(prefix: S) -> Self {
         Self::new(Level::Trace, prefix)
     }
     /// Log as [`Level::Warn`]
     /// # Arguments
-    /// * `prefix` - A prefix to prepend to the beginning of the log statment
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
     pub fn warn<S: Into<Cow<'a, str>>>(prefix: S) -> Self {
         Self::new(Level::Warn, prefix)
     }
+    /// Only log up to the given number of elements for each input, which avoids flooding the log with large inputs
+    /// # Arguments
+    /// * `max_elements` - The maximum number of elements to log per input
+    pub fn with_max_elements(mut self, max_elements: usize) -> Self {
+        self.max_elements = Some(max_elements);
+        self
+    }
 }
-impl<'a, T: Display> Service for LogOptionalDisplayService<'a, T> {
-    type Input = Option<T>;
-    type Output = Option<T>;
+impl<'a, T: Display> Service for LogEachDisplayService<'a, T> {
+    type Input = Vec<T>;
+    type Output = Vec<T>;
     type Error = ();
-    fn process(&self, input: Option<T>) -> Result<Self::Output, Self::Error> {
-        if let Some(input) = &input {
-            log::log!(self.level, "{}{}", self.prefix, input);
+    fn process(&self, input: Vec<T>) -> Result<Self::Output, Self::Error> {
+        let max_elements = self.max_elements.unwrap_or(usize::MAX);
+        for (index, element) in input.iter().enumerate().take(max_elements) {
+            self.config.log(format_args!("[{}] {}", index, element));
         }
         Ok(input)
     }
 }
+
+/// A [`sod::Service`] that times an underlying service, logging the elapsed time at a configured log level to [`log::log`] only when it exceeds a threshold, returning the output of the underlying service.
+///
+/// This service is useful for latency alerting, as nothing is logged while the underlying service is within its time budget.
+pub struct LogSlowService<'a, S> {
+    config: LogConfig<'a>,
+    threshold: Duration,
+    service: S,
+}
+impl<'a, S> LogSlowService<'a, S> {
+    /// Log slow processing at the given log level
+    /// # Arguments
+    /// * `level` - The log level
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `threshold` - The elapsed time the underlying service must exceed to be logged
+    /// * `service` - The underlying service to time
+    pub fn new<P: Into<Cow<'a, str>>>(
+        level: Level,
+        prefix: P,
+        threshold: Duration,
+        service: S,
+    ) -> Self {
+        Self {
+            config: LogConfig::new(level, prefix.into()),
+            threshold,
+            service,
+        }
+    }
+    /// Log as [`Level::Debug`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `threshold` - The elapsed time the underlying service must exceed to be logged
+    /// * `service` - The underlying service to time
+    pub fn debug<P: Into<Cow<'a, str>>>(prefix: P, threshold: Duration, service: S) -> Self {
+        Self::new(Level::Debug, prefix, threshold, service)
+    }
+    /// Log as [`Level::Error`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `threshold` - The elapsed time the underlying service must exceed to be logged
+    /// * `service` - The underlying service to time
+    pub fn error<P: Into<Cow<'a, str>>>(prefix: P, threshold: Duration, service: S) -> Self {
+        Self::new(Level::Error, prefix, threshold, service)
+    }
+    /// Log as [`Level::Info`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `threshold` - The elapsed time the underlying service must exceed to be logged
+    /// * `service` - The underlying service to time
+    pub fn info<P: Into<Cow<'a, str>>>(prefix: P, threshold: Duration, service: S) -> Self {
+        Self::new(Level::Info, prefix, threshold, service)
+    }
+    /// Log as [`Level::Trace`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `threshold` - The elapsed time the underlying service must exceed to be logged
+    /// * `service` - The underlying service to time
+    pub fn trace<P: Into<Cow<'a, str>>>(prefix: P, threshold: Duration, service: S) -> Self {
+        Self::new(Level::Trace, prefix, threshold, service)
+    }
+    /// Log as [`Level::Warn`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `threshold` - The elapsed time the underlying service must exceed to be logged
+    /// * `service` - The underlying service to time
+    pub fn warn<P: Into<Cow<'a, str>>>(prefix: P, threshold: Duration, service: S) -> Self {
+        Self::new(Level::Warn, prefix, threshold, service)
+    }
+}
+impl<'a, S: Service> Service for LogSlowService<'a, S> {
+    type Input = S::Input;
+    type Output = S::Output;
+    type Error = S::Error;
+    fn process(&self, input: S::Input) -> Result<Self::Output, Self::Error> {
+        let start = Instant::now();
+        let result = self.service.process(input);
+        let elapsed = start.elapsed();
+        if elapsed > self.threshold {
+            self.config.log(format_args!("{:?}", elapsed));
+        }
+        result
+    }
+}
+
+/// A [`sod::Service`] that logs [`Display`] input at a log level encoded in its type to [`log::log`], returning the input as output.
+///
+/// `LEVEL` is the [`Level`] as a `usize`, such as `{ Level::Info as usize }`, which allows the compiler to eliminate disabled
+/// logging entirely when combined with the static max level features of [`log`]. An invalid `LEVEL` fails to compile.
+/// Since the level is part of the type, it cannot be replaced after construction, so this service has no `with_level` or `with_filter` builders.
+/// ```
+/// use log::Level;
+/// use sod::Service;
+/// use sod_log::LogDisplayServiceConst;
+///
+/// let logging_service = LogDisplayServiceConst::<_, { Level::Info as usize }>::new("my event: ");
+/// logging_service.process("hello world!").unwrap();
+/// ```
+pub struct LogDisplayServiceConst<'a, T, const LEVEL: usize> {
+    config: LogConfig<'a>,
+    _phantom: PhantomData<fn(T)>,
+}
+impl<'a, T, const LEVEL: usize> LogDisplayServiceConst<'a, T, LEVEL> {
+    const LEVEL: Level = match LEVEL {
+        1 => Level::Error,
+        2 => Level::Warn,
+        3 => Level::Info,
+        4 => Level::Debug,
+        5 => Level::Trace,
+        _ => panic!("LEVEL must be a valid log::Level as usize"),
+    };
+    /// Log input at the level of the type
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    pub fn new<S: Into<Cow<'a, str>>>(prefix: S) -> Self {
+        Self {
+            config: LogConfig::new(Self::LEVEL, prefix.into()),
+            _phantom: PhantomData,
+        }
+    }
+}
+impl<'a, T: Display, const LEVEL: usize> Service for LogDisplayServiceConst<'a, T, LEVEL> {
+    type Input = T;
+    type Output = T;
+    type Error = ();
+    fn process(&self, input: T) -> Result<Self::Output, Self::Error> {
+        self.config
+            .emit_at(Self::LEVEL, &self.config.prefix, format_args!("{}", input));
+        Ok(input)
+    }
+}
+
+/// A [`sod::Service`] that logs [`Display`] input at a configured log level to [`log::log`] and sends the log statement to a channel, returning the input as output.
+///
+/// This service is useful for mirroring log statements to in-process consumers, such as a metrics aggregator or UI.
+/// Only log statements that are emitted are sent, including their tags and the default prefix.
+/// A disconnected channel does not fail the service, but is logged as a warning the first time it is encountered.
+pub struct LogChannelService<'a, T> {
+    config: LogConfig<'a>,
+    sender: Sender<String>,
+    disconnected: AtomicBool,
+    _phantom: PhantomData<fn(T)>,
+}
+impl<'a, T> LogChannelService<'a, T> {
+    /// Log input at the given log level
+    /// # Arguments
+    /// * `level` - The log level
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `sender` - The channel to send log statements to
+    pub fn new<S: Into<Cow<'a, str>>>(level: Level, prefix: S, sender: Sender<String>) -> Self {
+        Self {
+            config: LogConfig::new(level, prefix.into()),
+            sender,
+            disconnected: AtomicBool::new(false),
+            _phantom: PhantomData,
+        }
+    }
+    /// Log as [`Level::Debug`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `sender` - The channel to send log statements to
+    pub fn debug<S: Into<Cow<'a, str>>>(prefix: S, sender: Sender<String>) -> Self {
+        Self::new(Level::Debug, prefix, sender)
+    }
+    /// Log as [`Level::Error`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `sender` - The channel to send log statements to
+    pub fn error<S: Into<Cow<'a, str>>>(prefix: S, sender: Sender<String>) -> Self {
+        Self::new(Level::Error, prefix, sender)
+    }
+    /// Log as [`Level::Info`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `sender` - The channel to send log statements to
+    pub fn info<S: Into<Cow<'a, str>>>(prefix: S, sender: Sender<String>) -> Self {
+        Self::new(Level::Info, prefix, sender)
+    }
+    /// Log as [`Level::Trace`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `sender` - The channel to send log statements to
+    pub fn trace<S: Into<Cow<'a, str>>>(prefix: S, sender: Sender<String>) -> Self {
+        Self::new(Level::Trace, prefix, sender)
+    }
+    /// Log as [`Level::Warn`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `sender` - The channel to send log statements to
+    pub fn warn<S: Into<Cow<'a, str>>>(prefix: S, sender: Sender<String>) -> Self {
+        Self::new(Level::Warn, prefix, sender)
+    }
+}
+impl<'a, T: Display> Service for LogChannelService<'a, T> {
+    type Input = T;
+    type Output = T;
+    type Error = ();
+    fn process(&self, input: T) -> Result<Self::Output, Self::Error> {
+        if !self.config.will_emit() {
+            return Ok(input);
+        }
+        let value = input.to_string();
+        self.config.log(format_args!("{}", value));
+        let message = self.config.line(
+            self.config.level,
+            self.config.prefix(self.config.level),
+            format_args!("{}", value),
+        );
+        if self.sender.send(message).is_err() && !self.disconnected.swap(true, Ordering::Relaxed) {
+            self.config.warn(format_args!(
+                "channel disconnected, log statements will no longer be sent"
+            ));
+        }
+        Ok(input)
+    }
+}
+
+/// A [`sod::Service`] that logs [`Display`] input at a configured log level to [`log::log`] after a prefix produced for each input, returning the input as output.
+///
+/// This service is useful when the prefix depends on runtime context, such as the current thread name or a task id.
+/// The function may produce an owned [`String`] or a [`Cow`] that borrows a static prefix. It is only called when the log statement will be emitted,
+/// which allows it to cheaply inject per-call context such as a request id.
+/// The level is checked before the function is called and again by [`log::log`], so a maximum level changed concurrently between the two checks
+/// may skip a statement that just became enabled, or pass one that just became disabled to the logger, which filters it as usual. Input is returned as output either way.
+/// ```
+/// use sod::Service;
+/// use sod_log::DynamicPrefixLogDisplayService;
+///
+/// let logging_service = DynamicPrefixLogDisplayService::info(|| format!("{:?}: ", std::thread::current().id()));
+/// logging_service.process("hello world!").unwrap();
+/// ```
+pub struct DynamicPrefixLogDisplayService<'a, T, F> {
+    config: LogConfig<'a>,
+    prefix: F,
+    _phantom: PhantomData<fn(T)>,
+}
+impl<'a, T, P: AsRef<str>, F: Fn() -> P> DynamicPrefixLogDisplayService<'a, T, F> {
+    /// Log input at the given log level
+    /// # Arguments
+    /// * `level` - The log level
+    /// * `prefix` - A function that produces the prefix to prepend to the beginning of each log statement
+    pub fn new(level: Level, prefix: F) -> Self {
+        Self {
+            config: LogConfig::new(level, Cow::Borrowed("")),
+            prefix,
+            _phantom: PhantomData,
+        }
+    }
+    /// Log as [`Level::Debug`]
+    /// # Arguments
+    /// * `prefix` - A function that produces the prefix to prepend to the beginning of each log statement
+    pub fn debug(prefix: F) -> Self {
+        Self::new(Level::Debug, prefix)
+    }
+    /// Log as [`Level::Error`]
+    /// # Arguments
+    /// * `prefix` - A function that produces the prefix to prepend to the beginning of each log statement
+    pub fn error(prefix: F) -> Self {
+        Self::new(Level::Error, prefix)
+    }
+    /// Log as [`Level::Info`]
+    /// # Arguments
+    /// * `prefix` - A function that produces the prefix to prepend to the beginning of each log statement
+    pub fn info(prefix: F) -> Self {
+        Self::new(Level::Info, prefix)
+    }
+    /// Log as [`Level::Trace`]
+    /// # Arguments
+    /// * `prefix` - A function that produces the prefix to prepend to the beginning of each log statement
+    pub fn trace(prefix: F) -> Self {
+        Self::new(Level::Trace, prefix)
+    }
+    /// Log as [`Level::Warn`]
+    /// # Arguments
+    /// * `prefix` - A function that produces the prefix to prepend to the beginning of each log statement
+    pub fn warn(prefix: F) -> Self {
+        Self::new(Level::Warn, prefix)
+    }
+}
+impl<'a, T: Display, P: AsRef<str>, F: Fn() -> P> Service
+    for DynamicPrefixLogDisplayService<'a, T, F>
+{
+    type Input = T;
+    type Output = T;
+    type Error = ();
+    fn process(&self, input: T) -> Result<Self::Output, Self::Error> {
+        if self.config.will_emit() {
+            let prefix = (self.prefix)();
+            self.config
+                .log_prefixed(prefix.as_ref(), format_args!("{}", input));
+        }
+        Ok(input)
+    }
+}
+
+/// The error returned by `try_new` when a prefix fails validation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PrefixError {
+    /// The prefix contains the given ASCII control character, such as a newline, which may be used for log injection
+    ControlCharacter(char),
+    /// The prefix is empty or only whitespace, which produces anonymous log statements
+    Empty,
+}
+impl Display for PrefixError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ControlCharacter(c) => {
+                write!(f, "prefix contains ASCII control character {:?}", c)
+            }
+            Self::Empty => f.write_str("prefix is empty or only whitespace"),
+        }
+    }
+}
+impl std::error::Error for PrefixError {}
+
+static DEFAULT_PREFIX: RwLock<String> = RwLock::new(String::new());
+
+/// Set a process-global default prefix, such as an application name, that is prepended to the prefix of every subsequently constructed log service.
+///
+/// The default prefix is read once when a service is constructed, so services constructed before calling this function are unaffected.
+/// It is safe to call from any thread, but should typically be called once at startup before any services are constructed.
+/// Use `without_default_prefix` to omit it from a specific service.
+/// [`LogSinkService`] passes only its own prefix to its sink, so the default prefix is not prepended by it.
+/// # Arguments
+/// * `prefix` - The default prefix
+pub fn set_default_prefix<S: Into<String>>(prefix: S) {
+    *DEFAULT_PREFIX
+        .write()
+        .unwrap_or_else(PoisonError::into_inner) = prefix.into();
+}
+
+/// Get the syslog severity of the given log level, as defined by [RFC 5424](https://www.rfc-editor.org/rfc/rfc5424#section-6.2.1).
+///
+/// The five log levels map to `3` (error), `4` (warning), `6` (informational), and `7` (debug), where both [`Level::Debug`] and [`Level::Trace`] map to `7`.
+/// # Arguments
+/// * `level` - The log level
+pub fn syslog_severity(level: Level) -> u8 {
+    match level {
+        Level::Error => 3,
+        Level::Warn => 4,
+        Level::Info => 6,
+        Level::Debug | Level::Trace => 7,
+    }
+}
+
+/// Parse a [`LevelFilter`] from the given environment variable, defaulting to [`LevelFilter::Info`] when it is not set
+fn filter_from_env(var: &str) -> Result<LevelFilter, ParseLevelError> {
+    match std::env::var_os(var) {
+        Some(value) => value.to_string_lossy().parse(),
+        None => Ok(LevelFilter::Info),
+    }
+}
+
+fn validate_prefix(prefix: &str) -> Result<(), PrefixError> {
+    match prefix.chars().find(char::is_ascii_control) {
+        Some(c) => Err(PrefixError::ControlCharacter(c)),
+        None => Ok(()),
+    }
+}
+
+/// A [`sod::Service`] that logs the first inputs and then a sample of subsequent [`Display`] input at a configured log level to [`log::log`], returning the input as output.
+///
+/// This service is useful for full startup diagnostics followed by steady-state sampling.
+pub struct HeadThenSampleLogDisplayService<'a, T> {
+    config: LogConfig<'a>,
+    head: u64,
+    sample_every: u64,
+    count: AtomicU64,
+    _phantom: PhantomData<fn(T)>,
+}
+impl<'a, T> HeadThenSampleLogDisplayService<'a, T> {
+    /// Log input at the given log level
+    /// # Arguments
+    /// * `level` - The log level
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `head` - The number of initial inputs to log
+    /// * `sample_every` - Log 1 in every `sample_every` inputs after the head, where `0` logs none
+    pub fn new<S: Into<Cow<'a, str>>>(
+        level: Level,
+        prefix: S,
+        head: u64,
+        sample_every: u64,
+    ) -> Self {
+        Self {
+            config: LogConfig::new(level, prefix.into()),
+            head,
+            sample_every,
+            count: AtomicU64::new(0),
+            _phantom: PhantomData,
+        }
+    }
+    /// Log as [`Level::Debug`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `head` - The number of initial inputs to log
+    /// * `sample_every` - Log 1 in every `sample_every` inputs after the head, where `0` logs none
+    pub fn debug<S: Into<Cow<'a, str>>>(prefix: S, head: u64, sample_every: u64) -> Self {
+        Self::new(Level::Debug, prefix, head, sample_every)
+    }
+    /// Log as [`Level::Error`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `head` - The number of initial inputs to log
+    /// * `sample_every` - Log 1 in every `sample_every` inputs after the head, where `0` logs none
+    pub fn error<S: Into<Cow<'a, str>>>(prefix: S, head: u64, sample_every: u64) -> Self {
+        Self::new(Level::Error, prefix, head, sample_every)
+    }
+    /// Log as [`Level::Info`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `head` - The number of initial inputs to log
+    /// * `sample_every` - Log 1 in every `sample_every` inputs after the head, where `0` logs none
+    pub fn info<S: Into<Cow<'a, str>>>(prefix: S, head: u64, sample_every: u64) -> Self {
+        Self::new(Level::Info, prefix, head, sample_every)
+    }
+    /// Log as [`Level::Trace`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `head` - The number of initial inputs to log
+    /// * `sample_every` - Log 1 in every `sample_every` inputs after the head, where `0` logs none
+    pub fn trace<S: Into<Cow<'a, str>>>(prefix: S, head: u64, sample_every: u64) -> Self {
+        Self::new(Level::Trace, prefix, head, sample_every)
+    }
+    /// Log as [`Level::Warn`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `head` - The number of initial inputs to log
+    /// * `sample_every` - Log 1 in every `sample_every` inputs after the head, where `0` logs none
+    pub fn warn<S: Into<Cow<'a, str>>>(prefix: S, head: u64, sample_every: u64) -> Self {
+        Self::new(Level::Warn, prefix, head, sample_every)
+    }
+}
+impl<'a, T: Display> Service for HeadThenSampleLogDisplayService<'a, T> {
+    type Input = T;
+    type Output = T;
+    type Error = ();
+    fn process(&self, input: T) -> Result<Self::Output, Self::Error> {
+        let count = self.count.fetch_add(1, Ordering::Relaxed);
+        if count < self.head || (count - self.head).checked_rem(self.sample_every) == Some(0) {
+            self.config.log(format_args!("{}", input));
+        }
+        Ok(input)
+    }
+}
+
+/// A [`sod::Service`] that logs [`Display`] input at a configured log level to [`log::log`] only when it equals a watched value, returning the input as output.
+///
+/// This service is useful for watchpoint-style debugging, where only a specific value passing through the chain is of interest.
+pub struct WatchLogDisplayService<'a, T> {
+    config: LogConfig<'a>,
+    watch: T,
+}
+impl<'a, T> WatchLogDisplayService<'a, T> {
+    /// Log matching input at the given log level
+    /// # Arguments
+    /// * `level` - The log level
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `watch` - The value that input must equal to be logged
+    pub fn new<S: Into<Cow<'a, str>>>(level: Level, prefix: S, watch: T) -> Self {
+        Self {
+            config: LogConfig::new(level, prefix.into()),
+            watch,
+        }
+    }
+    /// Log as [`Level::Debug`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `watch` - The value that input must equal to be logged
+    pub fn debug<S: Into<Cow<'a, str>>>(prefix: S, watch: T) -> Self {
+        Self::new(Level::Debug, prefix, watch)
+    }
+    /// Log as [`Level::Error`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `watch` - The value that input must equal to be logged
+    pub fn error<S: Into<Cow<'a, str>>>(prefix: S, watch: T) -> Self {
+        Self::new(Level::Error, prefix, watch)
+    }
+    /// Log as [`Level::Info`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `watch` - The value that input must equal to be logged
+    pub fn info<S: Into<Cow<'a, str>>>(prefix: S, watch: T) -> Self {
+        Self::new(Level::Info, prefix, watch)
+    }
+    /// Log as [`Level::Trace`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `watch` - The value that input must equal to be logged
+    pub fn trace<S: Into<Cow<'a, str>>>(prefix: S, watch: T) -> Self {
+        Self::new(Level::Trace, prefix, watch)
+    }
+    /// Log as [`Level::Warn`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `watch` - The value that input must equal to be logged
+    pub fn warn<S: Into<Cow<'a, str>>>(prefix: S, watch: T) -> Self {
+        Self::new(Level::Warn, prefix, watch)
+    }
+}
+impl<'a, T: PartialEq + Display> Service for WatchLogDisplayService<'a, T> {
+    type Input = T;
+    type Output = T;
+    type Error = ();
+    fn process(&self, input: T) -> Result<Self::Output, Self::Error> {
+        if input == self.watch {
+            self.config.log(format_args!("{}", input));
+        }
+        Ok(input)
+    }
+}
+
+/// The formatting style of a [`LogService`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FormatStyle {
+    /// Format input with [`Debug`] as `{:?}`
+    Debug,
+    /// Format input with [`Display`] as `{}`
+    Display,
+    /// Format input with [`Debug`] as `{:#?}`
+    PrettyDebug,
+}
+
+/// A [`sod::Service`] that logs input with a [`FormatStyle`] chosen at runtime at a configured log level to [`log::log`], returning the input as output.
+///
+/// Each constructor requires the trait that its style formats with, which allows a single type to be used for every style.
+/// The constructors are named after the style, such as [`LogService::debug`], and take the log level as an argument,
+/// unlike the level-named constructors of the other services, such as [`LogDisplayService::debug`].
+pub struct LogService<'a, T> {
+    config: LogConfig<'a>,
+    style: FormatStyle,
+    format: fn(&T, &mut fmt::Formatter<'_>) -> fmt::Result,
+}
+impl<'a, T> LogService<'a, T> {
+    fn with_style<S: Into<Cow<'a, str>>>(
+        level: Level,
+        prefix: S,
+        style: FormatStyle,
+        format: fn(&T, &mut fmt::Formatter<'_>) -> fmt::Result,
+    ) -> Self {
+        Self {
+            config: LogConfig::new(level, prefix.into()),
+            style,
+            format,
+        }
+    }
+    /// Get the configured formatting style
+    pub fn style(&self) -> FormatStyle {
+        self.style
+    }
+}
+impl<'a, T: Debug> LogService<'a, T> {
+    /// Log input formatted with [`FormatStyle::Debug`] at the given log level
+    /// # Arguments
+    /// * `level` - The log level
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    pub fn debug<S: Into<Cow<'a, str>>>(level: Level, prefix: S) -> Self {
+        Self::with_style(level, prefix, FormatStyle::Debug, Debug::fmt)
+    }
+    /// Log input formatted with [`FormatStyle::PrettyDebug`] at the given log level
+    /// # Arguments
+    /// * `level` - The log level
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    pub fn pretty_debug<S: Into<Cow<'a, str>>>(level: Level, prefix: S) -> Self {
+        Self::with_style(level, prefix, FormatStyle::PrettyDebug, |input, f| {
+            write!(f, "{:#?}", input)
+        })
+    }
+}
+impl<'a, T: Display> LogService<'a, T> {
+    /// Log input formatted with [`FormatStyle::Display`] at the given log level
+    /// # Arguments
+    /// * `level` - The log level
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    pub fn display<S: Into<Cow<'a, str>>>(level: Level, prefix: S) -> Self {
+        Self::with_style(level, prefix, FormatStyle::Display, Display::fmt)
+    }
+}
+impl<'a, T> Service for LogService<'a, T> {
+    type Input = T;
+    type Output = T;
+    type Error = ();
+    fn process(&self, input: T) -> Result<Self::Output, Self::Error> {
+        self.config
+            .log(format_args!("{}", FormatWith(&input, self.format)));
+        Ok(input)
+    }
+}
+
+/// Formats a value with the given function as [`Display`]
+struct FormatWith<'t, T>(&'t T, fn(&T, &mut fmt::Formatter<'_>) -> fmt::Result);
+impl<'t, T> Display for FormatWith<'t, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        (self.1)(self.0, f)
+    }
+}
+
+/// A [`sod::Service`] that logs a heartbeat at a configured log level to [`log::log`] when an interval has elapsed since the last heartbeat, returning the input as output.
+///
+/// This service is useful for knowing that a non-blocking service chain that may go quiet is still alive.
+/// The interval is checked each time input is processed, and the first heartbeat is logged once the interval has elapsed since construction.
+pub struct HeartbeatLogService<'a, T> {
+    config: LogConfig<'a>,
+    interval: Duration,
+    last_heartbeat: Mutex<Instant>,
+    _phantom: PhantomData<fn(T)>,
+}
+impl<'a, T> HeartbeatLogService<'a, T> {
+    /// Log heartbeats at the given log level
+    /// # Arguments
+    /// * `level` - The log level
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `interval` - The minimum duration between heartbeats
+    pub fn new<S: Into<Cow<'a, str>>>(level: Level, prefix: S, interval: Duration) -> Self {
+        Self {
+            config: LogConfig::new(level, prefix.into()),
+            interval,
+            last_heartbeat: Mutex::new(Instant::now()),
+            _phantom: PhantomData,
+        }
+    }
+    /// Log as [`Level::Debug`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `interval` - The minimum duration between heartbeats
+    pub fn debug<S: Into<Cow<'a, str>>>(prefix: S, interval: Duration) -> Self {
+        Self::new(Level::Debug, prefix, interval)
+    }
+    /// Log as [`Level::Error`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `interval` - The minimum duration between heartbeats
+    pub fn error<S: Into<Cow<'a, str>>>(prefix: S, interval: Duration) -> Self {
+        Self::new(Level::Error, prefix, interval)
+    }
+    /// Log as [`Level::Info`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `interval` - The minimum duration between heartbeats
+    pub fn info<S: Into<Cow<'a, str>>>(prefix: S, interval: Duration) -> Self {
+        Self::new(Level::Info, prefix, interval)
+    }
+    /// Log as [`Level::Trace`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `interval` - The minimum duration between heartbeats
+    pub fn trace<S: Into<Cow<'a, str>>>(prefix: S, interval: Duration) -> Self {
+        Self::new(Level::Trace, prefix, interval)
+    }
+    /// Log as [`Level::Warn`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `interval` - The minimum duration between heartbeats
+    pub fn warn<S: Into<Cow<'a, str>>>(prefix: S, interval: Duration) -> Self {
+        Self::new(Level::Warn, prefix, interval)
+    }
+}
+impl<'a, T> Service for HeartbeatLogService<'a, T> {
+    type Input = T;
+    type Output = T;
+    type Error = ();
+    fn process(&self, input: T) -> Result<Self::Output, Self::Error> {
+        let now = Instant::now();
+        let due = {
+            let mut last_heartbeat = self
+                .last_heartbeat
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner);
+            let due = now.duration_since(*last_heartbeat) >= self.interval;
+            if due {
+                *last_heartbeat = now;
+            }
+            due
+        };
+        // the lock is released before logging, so a slow logger does not block other threads
+        if due {
+            self.config.log(format_args!("alive"));
+        }
+        Ok(input)
+    }
+}
+
+/// A [`sod::Service`] that times an underlying service, logging the elapsed time at a configured log level to [`log::log`], returning the output of the underlying service.
+///
+/// The elapsed time is logged in the form of `{prefix} took {elapsed:?}`, including when the underlying service returns an error.
+pub struct LogTimedService<'a, S> {
+    config: LogConfig<'a>,
+    service: S,
+}
+impl<'a, S> LogTimedService<'a, S> {
+    /// Log the elapsed time at the given log level
+    /// # Arguments
+    /// * `level` - The log level
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement, such as the name of the underlying service
+    /// * `service` - The underlying service to time
+    pub fn new<P: Into<Cow<'a, str>>>(level: Level, prefix: P, service: S) -> Self {
+        Self {
+            config: LogConfig::new(level, prefix.into()),
+            service,
+        }
+    }
+    /// Log as [`Level::Debug`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement, such as the name of the underlying service
+    /// * `service` - The underlying service to time
+    pub fn debug<P: Into<Cow<'a, str>>>(prefix: P, service: S) -> Self {
+        Self::new(Level::Debug, prefix, service)
+    }
+    /// Log as [`Level::Error`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement, such as the name of the underlying service
+    /// * `service` - The underlying service to time
+    pub fn error<P: Into<Cow<'a, str>>>(prefix: P, service: S) -> Self {
+        Self::new(Level::Error, prefix, service)
+    }
+    /// Log as [`Level::Info`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement, such as the name of the underlying service
+    /// * `service` - The underlying service to time
+    pub fn info<P: Into<Cow<'a, str>>>(prefix: P, service: S) -> Self {
+        Self::new(Level::Info, prefix, service)
+    }
+    /// Log as [`Level::Trace`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement, such as the name of the underlying service
+    /// * `service` - The underlying service to time
+    pub fn trace<P: Into<Cow<'a, str>>>(prefix: P, service: S) -> Self {
+        Self::new(Level::Trace, prefix, service)
+    }
+    /// Log as [`Level::Warn`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement, such as the name of the underlying service
+    /// * `service` - The underlying service to time
+    pub fn warn<P: Into<Cow<'a, str>>>(prefix: P, service: S) -> Self {
+        Self::new(Level::Warn, prefix, service)
+    }
+}
+impl<'a, S: Service> Service for LogTimedService<'a, S> {
+    type Input = S::Input;
+    type Output = S::Output;
+    type Error = S::Error;
+    fn process(&self, input: S::Input) -> Result<Self::Output, Self::Error> {
+        let start = Instant::now();
+        let result = self.service.process(input);
+        self.config.log(format_args!(" took {:?}", start.elapsed()));
+        result
+    }
+}
+
+/// The case to log formatted input as, set with `with_case`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LogCase {
+    /// Convert input to uppercase
+    Upper,
+    /// Convert input to lowercase
+    Lower,
+    /// Leave input as it is formatted
+    #[default]
+    AsIs,
+}
+
+/// A [`sod::Service`] that logs key-value pairs extracted from input as structured fields at a configured log level to [`log::log`], returning the input as output.
+///
+/// The prefix is logged as the message, and the extracted fields are attached to the [`log::Record`] as key-values,
+/// followed by any typed fields set with `with_field_i64`, `with_field_f64`, or `with_field_bool`.
+/// This service requires the `kv` feature.
+#[cfg(feature = "kv")]
+pub struct LogFieldsService<'a, T, F> {
+    config: LogConfig<'a>,
+    fields: F,
+    static_fields: Vec<(&'static str, FieldValue)>,
+    _phantom: PhantomData<fn(T)>,
+}
+#[cfg(feature = "kv")]
+impl<'a, T, F: Fn(&T) -> Vec<(&'static str, String)>> LogFieldsService<'a, T, F> {
+    /// Log fields at the given log level
+    /// # Arguments
+    /// * `level` - The log level
+    /// * `prefix` - The message of the log statement
+    /// * `fields` - A function that extracts key-value pairs from input
+    pub fn new<S: Into<Cow<'a, str>>>(level: Level, prefix: S, fields: F) -> Self {
+        Self {
+            config: LogConfig::new(level, prefix.into()),
+            fields,
+            static_fields: Vec::new(),
+            _phantom: PhantomData,
+        }
+    }
+    /// Log as [`Level::Debug`]
+    /// # Arguments
+    /// * `prefix` - The message of the log statement
+    /// * `fields` - A function that extracts key-value pairs from input
+    pub fn debug<S: Into<Cow<'a, str>>>(prefix: S, fields: F) -> Self {
+        Self::new(Level::Debug, prefix, fields)
+    }
+    /// Log as [`Level::Error`]
+    /// # Arguments
+    /// * `prefix` - The message of the log statement
+    /// * `fields` - A function that extracts key-value pairs from input
+    pub fn error<S: Into<Cow<'a, str>>>(prefix: S, fields: F) -> Self {
+        Self::new(Level::Error, prefix, fields)
+    }
+    /// Log as [`Level::Info`]
+    /// # Arguments
+    /// * `prefix` - The message of the log statement
+    /// * `fields` - A function that extracts key-value pairs from input
+    pub fn info<S: Into<Cow<'a, str>>>(prefix: S, fields: F) -> Self {
+        Self::new(Level::Info, prefix, fields)
+    }
+    /// Log as [`Level::Trace`]
+    /// # Arguments
+    /// * `prefix` - The message of the log statement
+    /// * `fields` - A function that extracts key-value pairs from input
+    pub fn trace<S: Into<Cow<'a, str>>>(prefix: S, fields: F) -> Self {
+        Self::new(Level::Trace, prefix, fields)
+    }
+    /// Log as [`Level::Warn`]
+    /// # Arguments
+    /// * `prefix` - The message of the log statement
+    /// * `fields` - A function that extracts key-value pairs from input
+    pub fn warn<S: Into<Cow<'a, str>>>(prefix: S, fields: F) -> Self {
+        Self::new(Level::Warn, prefix, fields)
+    }
+    /// Attach an integer field to every log statement, which is preserved as a number by structured sinks
+    /// # Arguments
+    /// * `key` - The field key
+    /// * `value` - The field value
+    pub fn with_field_i64(mut self, key: &'static str, value: i64) -> Self {
+        self.static_fields.push((key, FieldValue::I64(value)));
+        self
+    }
+    /// Attach a floating point field to every log statement, which is preserved as a number by structured sinks
+    /// # Arguments
+    /// * `key` - The field key
+    /// * `value` - The field value
+    pub fn with_field_f64(mut self, key: &'static str, value: f64) -> Self {
+        self.static_fields.push((key, FieldValue::F64(value)));
+        self
+    }
+    /// Attach a boolean field to every log statement, which is preserved as a boolean by structured sinks
+    /// # Arguments
+    /// * `key` - The field key
+    /// * `value` - The field value
+    pub fn with_field_bool(mut self, key: &'static str, value: bool) -> Self {
+        self.static_fields.push((key, FieldValue::Bool(value)));
+        self
+    }
+}
+#[cfg(feature = "kv")]
+impl<'a, T, F: Fn(&T) -> Vec<(&'static str, String)>> Service for LogFieldsService<'a, T, F> {
+    type Input = T;
+    type Output = T;
+    type Error = ();
+    fn process(&self, input: T) -> Result<Self::Output, Self::Error> {
+        if self.config.is_enabled() {
+            let fields = (self.fields)(&input);
+            let sources: [&dyn log::kv::Source; 2] = [&fields, &self.static_fields];
+            self.config.emit_key_values(format_args!(""), &sources);
+        }
+        Ok(input)
+    }
+}
+
+/// A typed value of a field attached by [`LogFieldsService`]
+#[cfg(feature = "kv")]
+enum FieldValue {
+    I64(i64),
+    F64(f64),
+    Bool(bool),
+}
+#[cfg(feature = "kv")]
+impl log::kv::ToValue for FieldValue {
+    fn to_value(&self) -> log::kv::Value<'_> {
+        match self {
+            FieldValue::I64(value) => log::kv::Value::from(*value),
+            FieldValue::F64(value) => log::kv::Value::from(*value),
+            FieldValue::Bool(value) => log::kv::Value::from(*value),
+        }
+    }
+}
+
+/// A [`sod::Service`] that logs [`Display`] input at a configured log level to [`log::log`] and sends a clone of the input to a channel, returning the input as output.
+///
+/// This service is useful for tests and tee-style debugging, where logged values are mirrored to a receiver.
+/// Like [`LogChannelService`], only input that is logged is sent, so nothing is cloned or sent while the level is disabled.
+/// A disconnected channel does not fail the service, but is logged as a warning the first time it is encountered.
+pub struct TeeLogDisplayService<'a, T> {
+    config: LogConfig<'a>,
+    sender: Sender<T>,
+    disconnected: AtomicBool,
+}
+impl<'a, T> TeeLogDisplayService<'a, T> {
+    /// Log input at the given log level
+    /// # Arguments
+    /// * `level` - The log level
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `sender` - The channel to send clones of input to
+    pub fn new<S: Into<Cow<'a, str>>>(level: Level, prefix: S, sender: Sender<T>) -> Self {
+        Self {
+            config: LogConfig::new(level, prefix.into()),
+            sender,
+            disconnected: AtomicBool::new(false),
+        }
+    }
+    /// Log as [`Level::Debug`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `sender` - The channel to send clones of input to
+    pub fn debug<S: Into<Cow<'a, str>>>(prefix: S, sender: Sender<T>) -> Self {
+        Self::new(Level::Debug, prefix, sender)
+    }
+    /// Log as [`Level::Error`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `sender` - The channel to send clones of input to
+    pub fn error<S: Into<Cow<'a, str>>>(prefix: S, sender: Sender<T>) -> Self {
+        Self::new(Level::Error, prefix, sender)
+    }
+    /// Log as [`Level::Info`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `sender` - The channel to send clones of input to
+    pub fn info<S: Into<Cow<'a, str>>>(prefix: S, sender: Sender<T>) -> Self {
+        Self::new(Level::Info, prefix, sender)
+    }
+    /// Log as [`Level::Trace`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `sender` - The channel to send clones of input to
+    pub fn trace<S: Into<Cow<'a, str>>>(prefix: S, sender: Sender<T>) -> Self {
+        Self::new(Level::Trace, prefix, sender)
+    }
+    /// Log as [`Level::Warn`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `sender` - The channel to send clones of input to
+    pub fn warn<S: Into<Cow<'a, str>>>(prefix: S, sender: Sender<T>) -> Self {
+        Self::new(Level::Warn, prefix, sender)
+    }
+}
+impl<'a, T: Display + Clone> Service for TeeLogDisplayService<'a, T> {
+    type Input = T;
+    type Output = T;
+    type Error = ();
+    fn process(&self, input: T) -> Result<Self::Output, Self::Error> {
+        if !self.config.will_emit() {
+            return Ok(input);
+        }
+        self.config.log(format_args!("{}", input));
+        if self.sender.send(input.clone()).is_err()
+            && !self.disconnected.swap(true, Ordering::Relaxed)
+        {
+            self.config.warn(format_args!(
+                "channel disconnected, input will no longer be sent"
+            ));
+        }
+        Ok(input)
+    }
+}
+
+/// A [`sod::Service`] that logs byte input as UTF-8 text, with invalid sequences replaced by `�`, at a configured log level to [`log::log`], returning the input as output.
+///
+/// This service is useful for debugging frames that mix text and binary data.
+pub struct LogUtf8LossyService<'a, T> {
+    config: LogConfig<'a>,
+    _phantom: PhantomData<fn(T)>,
+}
+impl<'a, T> LogUtf8LossyService<'a, T> {
+    /// Log input at the given log level
+    /// # Arguments
+    /// * `level` - The log level
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    pub fn new<S: Into<Cow<'a, str>>>(level: Level, prefix: S) -> Self {
+        Self {
+            config: LogConfig::new(level, prefix.into()),
+            _phantom: PhantomData,
+        }
+    }
+    /// Log as [`Level::Debug`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    pub fn debug<S: Into<Cow<'a, str>>>(prefix: S) -> Self {
+        Self::new(Level::Debug, prefix)
+    }
+    /// Log as [`Level::Error`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    pub fn error<S: Into<Cow<'a, str>>>(prefix: S) -> Self {
+        Self::new(Level::Error, prefix)
+    }
+    /// Log as [`Level::Info`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    pub fn info<S: Into<Cow<'a, str>>>(prefix: S) -> Self {
+        Self::new(Level::Info, prefix)
+    }
+    /// Log as [`Level::Trace`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    pub fn trace<S: Into<Cow<'a, str>>>(prefix: S) -> Self {
+        Self::new(Level::Trace, prefix)
+    }
+    /// Log as [`Level::Warn`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    pub fn warn<S: Into<Cow<'a, str>>>(prefix: S) -> Self {
+        Self::new(Level::Warn, prefix)
+    }
+}
+impl<'a, T: AsRef<[u8]>> Service for LogUtf8LossyService<'a, T> {
+    type Input = T;
+    type Output = T;
+    type Error = ();
+    fn process(&self, input: T) -> Result<Self::Output, Self::Error> {
+        // decoding allocates when the input is not valid UTF-8, so it is skipped when nothing would be logged
+        if self.config.will_emit() {
+            self.config
+                .log(format_args!("{}", String::from_utf8_lossy(input.as_ref())));
+        }
+        Ok(input)
+    }
+}
+
+/// A function that estimates the heap size of input in bytes
+type HeapSize<'a, T> = Box<dyn Fn(&T) -> usize + Send + Sync + 'a>;
+
+/// A [`sod::Service`] that logs the memory size of input at a configured log level to [`log::log`], returning the input as output.
+///
+/// The size is the shallow size returned by [`std::mem::size_of_val`], logged in the form of `{prefix}{size} bytes`.
+/// Heap allocations owned by the input, such as the elements of a [`Vec`], are not included,
+/// unless estimated by a function configured with [`LogSizeService::with_heap_size`], which is logged in the form of `{prefix}{size} bytes (+{heap} heap)`.
+pub struct LogSizeService<'a, T> {
+    config: LogConfig<'a>,
+    heap_size: Option<HeapSize<'a, T>>,
+}
+impl<'a, T> LogSizeService<'a, T> {
+    /// Log input at the given log level
+    /// # Arguments
+    /// * `level` - The log level
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    pub fn new<S: Into<Cow<'a, str>>>(level: Level, prefix: S) -> Self {
+        Self {
+            config: LogConfig::new(level, prefix.into()),
+            heap_size: None,
+        }
+    }
+    /// Log as [`Level::Debug`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    pub fn debug<S: Into<Cow<'a, str>>>(prefix: S) -> Self {
+        Self::new(Level::Debug, prefix)
+    }
+    /// Log as [`Level::Error`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    pub fn error<S: Into<Cow<'a, str>>>(prefix: S) -> Self {
+        Self::new(Level::Error, prefix)
+    }
+    /// Log as [`Level::Info`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    pub fn info<S: Into<Cow<'a, str>>>(prefix: S) -> Self {
+        Self::new(Level::Info, prefix)
+    }
+    /// Log as [`Level::Trace`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    pub fn trace<S: Into<Cow<'a, str>>>(prefix: S) -> Self {
+        Self::new(Level::Trace, prefix)
+    }
+    /// Log as [`Level::Warn`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    pub fn warn<S: Into<Cow<'a, str>>>(prefix: S) -> Self {
+        Self::new(Level::Warn, prefix)
+    }
+    /// Estimate the heap allocations owned by input with the given function, such as `|v: &Vec<u64>| v.capacity() * 8`
+    /// # Arguments
+    /// * `heap_size` - A function that estimates the heap size of input in bytes
+    pub fn with_heap_size<F: Fn(&T) -> usize + Send + Sync + 'a>(mut self, heap_size: F) -> Self {
+        self.heap_size = Some(Box::new(heap_size));
+        self
+    }
+}
+impl<'a, T> Service for LogSizeService<'a, T> {
+    type Input = T;
+    type Output = T;
+    type Error = ();
+    fn process(&self, input: T) -> Result<Self::Output, Self::Error> {
+        let size = std::mem::size_of_val(&input);
+        match &self.heap_size {
+            Some(heap_size) => {
+                self.config
+                    .log(format_args!("{} bytes (+{} heap)", size, heap_size(&input)))
+            }
+            None => self.config.log(format_args!("{} bytes", size)),
+        }
+        Ok(input)
+    }
+}
+
+/// A [`sod::Service`] that logs a bounded preview of [`Debug`] elements of [`Vec`] input at a configured log level to [`log::log`], returning the input as output.
+///
+/// The preview is logged in the form of `[1, 2, 3, … (+997 more)]`, and includes up to 10 elements unless configured with [`LogPreviewService::with_preview_len`].
+pub struct LogPreviewService<'a, T> {
+    config: LogConfig<'a>,
+    preview_len: usize,
+    _phantom: PhantomData<fn(T)>,
+}
+impl<'a, T> LogPreviewService<'a, T> {
+    /// Log input at the given log level
+    /// # Arguments
+    /// * `level` - The log level
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    pub fn new<S: Into<Cow<'a, str>>>(level: Level, prefix: S) -> Self {
+        Self {
+            config: LogConfig::new(level, prefix.into()),
+            preview_len: 10,
+            _phantom: PhantomData,
+        }
+    }
+    /// Log as [`Level::Debug`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    pub fn debug<S: Into<Cow<'a, str>>>(prefix: S) -> Self {
+        Self::new(Level::Debug, prefix)
+    }
+    /// Log as [`Level::Error`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    pub fn error<S: Into<Cow<'a, str>>>(prefix: S) -> Self {
+        Self::new(Level::Error, prefix)
+    }
+    /// Log as [`Level::Info`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    pub fn info<S: Into<Cow<'a, str>>>(prefix: S) -> Self {
+        Self::new(Level::Info, prefix)
+    }
+    /// Log as [`Level::Trace`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    pub fn trace<S: Into<Cow<'a, str>>>(prefix: S) -> Self {
+        Self::new(Level::Trace, prefix)
+    }
+    /// Log as [`Level::Warn`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    pub fn warn<S: Into<Cow<'a, str>>>(prefix: S) -> Self {
+        Self::new(Level::Warn, prefix)
+    }
+    /// Set the maximum number of elements to include in the preview
+    /// # Arguments
+    /// * `preview_len` - The maximum number of elements to log
+    pub fn with_preview_len(mut self, preview_len: usize) -> Self {
+        self.preview_len = preview_len;
+        self
+    }
+}
+impl<'a, T: Debug> Service for LogPreviewService<'a, T> {
+    type Input = Vec<T>;
+    type Output = Vec<T>;
+    type Error = ();
+    fn process(&self, input: Vec<T>) -> Result<Self::Output, Self::Error> {
+        self.config
+            .log(format_args!("{}", Preview(&input, self.preview_len)));
+        Ok(input)
+    }
+}
+
+/// Formats up to the given number of elements of a slice, followed by the count of remaining elements
+struct Preview<'t, T>(&'t [T], usize);
+impl<'t, T: Debug> Display for Preview<'t, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (shown, remaining) = self.0.split_at(self.1.min(self.0.len()));
+        f.write_str("[")?;
+        for (index, element) in shown.iter().enumerate() {
+            if index > 0 {
+                f.write_str(", ")?;
+            }
+            write!(f, "{:?}", element)?;
+        }
+        if !remaining.is_empty() {
+            if !shown.is_empty() {
+                f.write_str(", ")?;
+            }
+            write!(f, "… (+{} more)", remaining.len())?;
+        }
+        f.write_str("]")
+    }
+}
+
+/// A [`sod::Service`] that logs integer input with grouped thousands, such as `1,234,567`, at a configured log level to [`log::log`], returning the input as output.
+///
+/// The separator defaults to `,` unless configured with [`LogNumberService::with_separator`].
+pub struct LogNumberService<'a, T> {
+    config: LogConfig<'a>,
+    separator: char,
+    _phantom: PhantomData<fn(T)>,
+}
+impl<'a, T> LogNumberService<'a, T> {
+    /// Log input at the given log level
+    /// # Arguments
+    /// * `level` - The log level
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    pub fn new<S: Into<Cow<'a, str>>>(level: Level, prefix: S) -> Self {
+        Self {
+            config: LogConfig::new(level, prefix.into()),
+            separator: ',',
+            _phantom: PhantomData,
+        }
+    }
+    /// Log as [`Level::Debug`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    pub fn debug<S: Into<Cow<'a, str>>>(prefix: S) -> Self {
+        Self::new(Level::Debug, prefix)
+    }
+    /// Log as [`Level::Error`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    pub fn error<S: Into<Cow<'a, str>>>(prefix: S) -> Self {
+        Self::new(Level::Error, prefix)
+    }
+    /// Log as [`Level::Info`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    pub fn info<S: Into<Cow<'a, str>>>(prefix: S) -> Self {
+        Self::new(Level::Info, prefix)
+    }
+    /// Log as [`Level::Trace`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    pub fn trace<S: Into<Cow<'a, str>>>(prefix: S) -> Self {
+        Self::new(Level::Trace, prefix)
+    }
+    /// Log as [`Level::Warn`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    pub fn warn<S: Into<Cow<'a, str>>>(prefix: S) -> Self {
+        Self::new(Level::Warn, prefix)
+    }
+    /// Set the character used to separate groups of thousands
+    /// # Arguments
+    /// * `separator` - The separator character
+    pub fn with_separator(mut self, separator: char) -> Self {
+        self.separator = separator;
+        self
+    }
+}
+impl<'a, T: Copy + Into<i128>> Service for LogNumberService<'a, T> {
+    type Input = T;
+    type Output = T;
+    type Error = ();
+    fn process(&self, input: T) -> Result<Self::Output, Self::Error> {
+        self.config
+            .log(format_args!("{}", Grouped(input.into(), self.separator)));
+        Ok(input)
+    }
+}
+
+/// Formats an integer with the given separator between groups of thousands
+struct Grouped(i128, char);
+impl Display for Grouped {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.0 < 0 {
+            f.write_str("-")?;
+        }
+        let digits = self.0.unsigned_abs().to_string();
+        for (index, digit) in digits.chars().enumerate() {
+            if index > 0 && (digits.len() - index) % 3 == 0 {
+                write!(f, "{}", self.1)?;
+            }
+            write!(f, "{}", digit)?;
+        }
+        Ok(())
+    }
+}
+
+/// The [`ServiceChain`] returned by `then_map`, which logs input with service `L` and then transforms it to `O` with function `F`
+pub type LogThenMapService<'a, L, O, F> = ServiceChain<
+    ServiceChain<NoOpService<'a, <L as Service>::Input>, L>,
+    FnService<<L as Service>::Input, O, (), F>,
+>;
+
+/// A [`sod::Service`] that logs [`Display`] input at a configured log level to [`log::log`] and retains the most recent log statements in memory, returning the input as output.
+///
+/// This service is useful for post-mortem debugging, where the recent log statements of a specific stage are accessed with [`RingBufferLogDisplayService::recent`].
+/// Only log statements that are emitted are retained, including their tags and the default prefix.
+pub struct RingBufferLogDisplayService<'a, T> {
+    config: LogConfig<'a>,
+    capacity: usize,
+    recent: Mutex<VecDeque<String>>,
+    _phantom: PhantomData<fn(T)>,
+}
+impl<'a, T> RingBufferLogDisplayService<'a, T> {
+    /// Log input at the given log level
+    /// # Arguments
+    /// * `level` - The log level
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `capacity` - The number of recent log statements to retain
+    pub fn new<S: Into<Cow<'a, str>>>(level: Level, prefix: S, capacity: usize) -> Self {
+        Self {
+            config: LogConfig::new(level, prefix.into()),
+            capacity,
+            recent: Mutex::new(VecDeque::with_capacity(capacity)),
+            _phantom: PhantomData,
+        }
+    }
+    /// Log as [`Level::Debug`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `capacity` - The number of recent log statements to retain
+    pub fn debug<S: Into<Cow<'a, str>>>(prefix: S, capacity: usize) -> Self {
+        Self::new(Level::Debug, prefix, capacity)
+    }
+    /// Log as [`Level::Error`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `capacity` - The number of recent log statements to retain
+    pub fn error<S: Into<Cow<'a, str>>>(prefix: S, capacity: usize) -> Self {
+        Self::new(Level::Error, prefix, capacity)
+    }
+    /// Log as [`Level::Info`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `capacity` - The number of recent log statements to retain
+    pub fn info<S: Into<Cow<'a, str>>>(prefix: S, capacity: usize) -> Self {
+        Self::new(Level::Info, prefix, capacity)
+    }
+    /// Log as [`Level::Trace`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `capacity` - The number of recent log statements to retain
+    pub fn trace<S: Into<Cow<'a, str>>>(prefix: S, capacity: usize) -> Self {
+        Self::new(Level::Trace, prefix, capacity)
+    }
+    /// Log as [`Level::Warn`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `capacity` - The number of recent log statements to retain
+    pub fn warn<S: Into<Cow<'a, str>>>(prefix: S, capacity: usize) -> Self {
+        Self::new(Level::Warn, prefix, capacity)
+    }
+    /// Get the most recent log statements, from oldest to newest
+    pub fn recent(&self) -> Vec<String> {
+        let recent = self.recent.lock().unwrap_or_else(PoisonError::into_inner);
+        recent.iter().cloned().collect()
+    }
+}
+impl<'a, T: Display> Service for RingBufferLogDisplayService<'a, T> {
+    type Input = T;
+    type Output = T;
+    type Error = ();
+    fn process(&self, input: T) -> Result<Self::Output, Self::Error> {
+        if !self.config.will_emit() {
+            return Ok(input);
+        }
+        self.config.log(format_args!("{}", input));
+        if self.capacity > 0 {
+            let level = self.config.level;
+            let message =
+                self.config
+                    .line(level, self.config.prefix(level), format_args!("{}", input));
+            let mut recent = self.recent.lock().unwrap_or_else(PoisonError::into_inner);
+            if recent.len() == self.capacity {
+                recent.pop_front();
+            }
+            recent.push_back(message);
+        }
+        Ok(input)
+    }
+}
+
+/// A [`sod::Service`] that logs [`Display`] input at a configured log level to [`log::log`] unless an equal value was logged within a time window, returning the input as output.
+///
+/// This service is useful for alert-style events, where duplicates within the window are suppressed even when they are not consecutive.
+/// The time each value was last logged is retained for up to 1024 distinct values unless configured with [`LogDedupService::with_max_entries`].
+/// When full, expired values are evicted first, followed by the value that was logged longest ago.
+pub struct LogDedupService<'a, T> {
+    config: LogConfig<'a>,
+    ttl: Duration,
+    max_entries: usize,
+    logged: Mutex<HashMap<T, Instant>>,
+}
+impl<'a, T> LogDedupService<'a, T> {
+    /// Log input at the given log level
+    /// # Arguments
+    /// * `level` - The log level
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `ttl` - The duration that a logged value is suppressed for
+    pub fn new<S: Into<Cow<'a, str>>>(level: Level, prefix: S, ttl: Duration) -> Self {
+        Self {
+            config: LogConfig::new(level, prefix.into()),
+            ttl,
+            max_entries: 1024,
+            logged: Mutex::new(HashMap::new()),
+        }
+    }
+    /// Log as [`Level::Debug`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `ttl` - The duration that a logged value is suppressed for
+    pub fn debug<S: Into<Cow<'a, str>>>(prefix: S, ttl: Duration) -> Self {
+        Self::new(Level::Debug, prefix, ttl)
+    }
+    /// Log as [`Level::Error`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `ttl` - The duration that a logged value is suppressed for
+    pub fn error<S: Into<Cow<'a, str>>>(prefix: S, ttl: Duration) -> Self {
+        Self::new(Level::Error, prefix, ttl)
+    }
+    /// Log as [`Level::Info`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `ttl` - The duration that a logged value is suppressed for
+    pub fn info<S: Into<Cow<'a, str>>>(prefix: S, ttl: Duration) -> Self {
+        Self::new(Level::Info, prefix, ttl)
+    }
+    /// Log as [`Level::Trace`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `ttl` - The duration that a logged value is suppressed for
+    pub fn trace<S: Into<Cow<'a, str>>>(prefix: S, ttl: Duration) -> Self {
+        Self::new(Level::Trace, prefix, ttl)
+    }
+    /// Log as [`Level::Warn`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `ttl` - The duration that a logged value is suppressed for
+    pub fn warn<S: Into<Cow<'a, str>>>(prefix: S, ttl: Duration) -> Self {
+        Self::new(Level::Warn, prefix, ttl)
+    }
+    /// Set the maximum number of distinct values to retain, which bounds memory usage
+    /// # Arguments
+    /// * `max_entries` - The maximum number of retained values
+    pub fn with_max_entries(mut self, max_entries: usize) -> Self {
+        self.max_entries = max_entries;
+        self
+    }
+}
+impl<'a, T: Hash + Eq + Clone + Display> Service for LogDedupService<'a, T> {
+    type Input = T;
+    type Output = T;
+    type Error = ();
+    fn process(&self, input: T) -> Result<Self::Output, Self::Error> {
+        let now = Instant::now();
+        let mut logged = self.logged.lock().unwrap_or_else(PoisonError::into_inner);
+        match logged.get(&input) {
+            Some(last) if now.duration_since(*last) < self.ttl => return Ok(input),
+            Some(_) => {}
+            None => {
+                if logged.len() >= self.max_entries {
+                    logged.retain(|_, last| now.duration_since(*last) < self.ttl);
+                }
+                if logged.len() >= self.max_entries {
+                    let oldest = logged
+                        .iter()
+                        .min_by_key(|(_, last)| **last)
+                        .map(|(value, _)| value.clone());
+                    if let Some(oldest) = oldest {
+                        logged.remove(&oldest);
+                    }
+                }
+            }
+        }
+        if self.max_entries > 0 {
+            logged.insert(input.clone(), now);
+        }
+        drop(logged);
+        self.config.log(format_args!("{}", input));
+        Ok(input)
+    }
+}
+
+/// A [`sod::Service`] that logs [`Duration`] input in a compact human-readable form, such as `1.23s` or `3m 4s`, at a configured log level to [`log::log`], returning the input as output.
+///
+/// Durations under a minute are logged with 2 decimal places in the largest fitting unit of `s`, `ms`, or `µs`, unless configured with [`LogDurationService::with_precision`].
+/// The unit is chosen after rounding, so `999.999ms` is logged as `1.00s` rather than `1000.00ms`.
+/// Durations of a minute or more are logged as whole minutes and seconds, or hours, minutes, and seconds.
+/// Use [`LogDurationService::from_fn`] to log a [`Duration`] extracted from other input, and [`LogDurationService::with_unit`] to log it in a fixed unit.
+pub struct LogDurationService<'a, T = Duration, F = fn(&Duration) -> Duration> {
+    config: LogConfig<'a>,
+    extract: F,
+    unit: DurationUnit,
+    precision: usize,
+    _phantom: PhantomData<fn(T)>,
+}
+impl<'a> LogDurationService<'a> {
+    /// Log input at the given log level
+    /// # Arguments
+    /// * `level` - The log level
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    pub fn new<S: Into<Cow<'a, str>>>(level: Level, prefix: S) -> Self {
+        Self {
+            config: LogConfig::new(level, prefix.into()),
+            extract: |duration| *duration,
+            unit: DurationUnit::Compact,
+            precision: 2,
+            _phantom: PhantomData,
+        }
+    }
+    /// Log as [`Level::Debug`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    pub fn debug<S: Into<Cow<'a, str>>>(prefix: S) -> Self {
+        Self::new(Level::Debug, prefix)
+    }
+    /// Log as [`Level::Error`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    pub fn error<S: Into<Cow<'a, str>>>(prefix: S) -> Self {
+        Self::new(Level::Error, prefix)
+    }
+    /// Log as [`Level::Info`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    pub fn info<S: Into<Cow<'a, str>>>(prefix: S) -> Self {
+        Self::new(Level::Info, prefix)
+    }
+    /// Log as [`Level::Trace`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    pub fn trace<S: Into<Cow<'a, str>>>(prefix: S) -> Self {
+        Self::new(Level::Trace, prefix)
+    }
+    /// Log as [`Level::Warn`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    pub fn warn<S: Into<Cow<'a, str>>>(prefix: S) -> Self {
+        Self::new(Level::Warn, prefix)
+    }
+}
+impl<'a, T, F: Fn(&T) -> Duration> LogDurationService<'a, T, F> {
+    /// Log a [`Duration`] extracted from input at the given log level
+    /// # Arguments
+    /// * `level` - The log level
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `extract` - A function that extracts the [`Duration`] to log from the input
+    pub fn from_fn<S: Into<Cow<'a, str>>>(level: Level, prefix: S, extract: F) -> Self {
+        Self {
+            config: LogConfig::new(level, prefix.into()),
+            extract,
+            unit: DurationUnit::Compact,
+            precision: 2,
+            _phantom: PhantomData,
+        }
+    }
+    /// Set the unit that durations are logged in
+    /// # Arguments
+    /// * `unit` - The unit
+    pub fn with_unit(mut self, unit: DurationUnit) -> Self {
+        self.unit = unit;
+        self
+    }
+    /// Set the number of decimal places logged for durations under a minute
+    /// # Arguments
+    /// * `precision` - The number of decimal places
+    pub fn with_precision(mut self, precision: usize) -> Self {
+        self.precision = precision;
+        self
+    }
+}
+impl<'a, T, F: Fn(&T) -> Duration> Service for LogDurationService<'a, T, F> {
+    type Input = T;
+    type Output = T;
+    type Error = ();
+    fn process(&self, input: T) -> Result<Self::Output, Self::Error> {
+        let duration = (self.extract)(&input);
+        self.config.log(format_args!(
+            "{}",
+            FormattedDuration(duration, self.unit, self.precision)
+        ));
+        Ok(input)
+    }
+}
+
+/// The unit that [`LogDurationService`] logs durations in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum DurationUnit {
+    /// The largest fitting unit, such as `1.23s` or `3m 4s`
+    #[default]
+    Compact,
+    /// Whole milliseconds, such as `1234ms`
+    Millis,
+    /// Whole microseconds, such as `1234567µs`
+    Micros,
+    /// Whole nanoseconds, such as `1234567891ns`
+    Nanos,
+}
+
+/// Formats a [`Duration`] in the given unit, using the given precision for compact durations under a minute
+struct FormattedDuration(Duration, DurationUnit, usize);
+impl Display for FormattedDuration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let FormattedDuration(duration, unit, precision) = *self;
+        match unit {
+            DurationUnit::Millis => return write!(f, "{}ms", duration.as_millis()),
+            DurationUnit::Micros => return write!(f, "{}µs", duration.as_micros()),
+            DurationUnit::Nanos => return write!(f, "{}ns", duration.as_nanos()),
+            DurationUnit::Compact => {}
+        }
+        // the unit is chosen after rounding, so a duration that rounds up to the next unit, such as 59.999s, is logged in that unit
+        // durations have nanosecond resolution, so rounding to more than 9 decimal places changes nothing
+        let scale = 10f64.powi(precision.min(9) as i32);
+        let round = |value: f64| (value * scale).round() / scale;
+        let secs_f64 = duration.as_secs_f64();
+        let secs = if round(secs_f64) >= 60.0 {
+            duration.as_secs().max(60)
+        } else {
+            0
+        };
+        if secs >= 3600 {
+            write!(f, "{}h {}m {}s", secs / 3600, secs % 3600 / 60, secs % 60)
+        } else if secs >= 60 {
+            write!(f, "{}m {}s", secs / 60, secs % 60)
+        } else if round(secs_f64) >= 1.0 {
+            write!(f, "{:.*}s", precision, round(secs_f64))
+        } else if round(secs_f64 * 1e3) >= 1.0 {
+            write!(f, "{:.*}ms", precision, round(secs_f64 * 1e3))
+        } else if duration.as_nanos() >= 1000 {
+            write!(f, "{:.*}µs", precision, round(secs_f64 * 1e6))
+        } else {
+            write!(f, "{}ns", duration.as_nanos())
+        }
+    }
+}
+
+/// A [`sod::Service`] that logs [`Debug`] input at a configured log level to [`log::log`] only when it differs from the previous input, returning the input as output.
+///
+/// Each log statement shows the transition as `old -> new`, where the first input is logged as `(init) -> new`.
+/// This service is useful for state machine pipelines, where only changes of state are of interest.
+pub struct LogTransitionService<'a, T> {
+    config: LogConfig<'a>,
+    last: Mutex<Option<T>>,
+}
+impl<'a, T> LogTransitionService<'a, T> {
+    /// Log input at the given log level
+    /// # Arguments
+    /// * `level` - The log level
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    pub fn new<S: Into<Cow<'a, str>>>(level: Level, prefix: S) -> Self {
+        Self {
+            config: LogConfig::new(level, prefix.into()),
+            last: Mutex::new(None),
+        }
+    }
+    /// Log as [`Level::Debug`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    pub fn debug<S: Into<Cow<'a, str>>>(prefix: S) -> Self {
+        Self::new(Level::Debug, prefix)
+    }
+    /// Log as [`Level::Error`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    pub fn error<S: Into<Cow<'a, str>>>(prefix: S) -> Self {
+        Self::new(Level::Error, prefix)
+    }
+    /// Log as [`Level::Info`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    pub fn info<S: Into<Cow<'a, str>>>(prefix: S) -> Self {
+        Self::new(Level::Info, prefix)
+    }
+    /// Log as [`Level::Trace`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    pub fn trace<S: Into<Cow<'a, str>>>(prefix: S) -> Self {
+        Self::new(Level::Trace, prefix)
+    }
+    /// Log as [`Level::Warn`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    pub fn warn<S: Into<Cow<'a, str>>>(prefix: S) -> Self {
+        Self::new(Level::Warn, prefix)
+    }
+}
+impl<'a, T: PartialEq + Debug + Clone> Service for LogTransitionService<'a, T> {
+    type Input = T;
+    type Output = T;
+    type Error = ();
+    fn process(&self, input: T) -> Result<Self::Output, Self::Error> {
+        let mut last = self.last.lock().unwrap_or_else(PoisonError::into_inner);
+        match last.as_ref() {
+            Some(old) if *old == input => {}
+            Some(old) => self.config.log(format_args!("{:?} -> {:?}", old, input)),
+            None => self.config.log(format_args!("(init) -> {:?}", input)),
+        }
+        *last = Some(input.clone());
+        Ok(input)
+    }
+}
+
+/// A [`sod::Service`] that logs [`Debug`] input at a configured log level to [`log::log`], returning the input as output, or a configured error when a predicate rejects the input.
+///
+/// This service turns a logging stage into a validation stage, short-circuiting a [`ServiceChain`] with `Err` for input that the predicate indicates is an error.
+/// Rejected input is still logged before the error is returned.
+pub struct LogOrErrService<'a, T, P, E> {
+    config: LogConfig<'a>,
+    predicate: P,
+    error: E,
+    _phantom: PhantomData<fn(T)>,
+}
+impl<'a, T, P: Fn(&T) -> bool, E: Clone> LogOrErrService<'a, T, P, E> {
+    /// Log input at the given log level
+    /// # Arguments
+    /// * `level` - The log level
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `predicate` - A function returning `true` when the input is an error
+    /// * `error` - The error to return for rejected input
+    pub fn new<S: Into<Cow<'a, str>>>(level: Level, prefix: S, predicate: P, error: E) -> Self {
+        Self {
+            config: LogConfig::new(level, prefix.into()),
+            predicate,
+            error,
+            _phantom: PhantomData,
+        }
+    }
+    /// Log as [`Level::Debug`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `predicate` - A function returning `true` when the input is an error
+    /// * `error` - The error to return for rejected input
+    pub fn debug<S: Into<Cow<'a, str>>>(prefix: S, predicate: P, error: E) -> Self {
+        Self::new(Level::Debug, prefix, predicate, error)
+    }
+    /// Log as [`Level::Error`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `predicate` - A function returning `true` when the input is an error
+    /// * `error` - The error to return for rejected input
+    pub fn error<S: Into<Cow<'a, str>>>(prefix: S, predicate: P, error: E) -> Self {
+        Self::new(Level::Error, prefix, predicate, error)
+    }
+    /// Log as [`Level::Info`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `predicate` - A function returning `true` when the input is an error
+    /// * `error` - The error to return for rejected input
+    pub fn info<S: Into<Cow<'a, str>>>(prefix: S, predicate: P, error: E) -> Self {
+        Self::new(Level::Info, prefix, predicate, error)
+    }
+    /// Log as [`Level::Trace`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `predicate` - A function returning `true` when the input is an error
+    /// * `error` - The error to return for rejected input
+    pub fn trace<S: Into<Cow<'a, str>>>(prefix: S, predicate: P, error: E) -> Self {
+        Self::new(Level::Trace, prefix, predicate, error)
+    }
+    /// Log as [`Level::Warn`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `predicate` - A function returning `true` when the input is an error
+    /// * `error` - The error to return for rejected input
+    pub fn warn<S: Into<Cow<'a, str>>>(prefix: S, predicate: P, error: E) -> Self {
+        Self::new(Level::Warn, prefix, predicate, error)
+    }
+}
+impl<'a, T: Debug, P: Fn(&T) -> bool, E: Clone> Service for LogOrErrService<'a, T, P, E> {
+    type Input = T;
+    type Output = T;
+    type Error = E;
+    fn process(&self, input: T) -> Result<Self::Output, Self::Error> {
+        self.config.log(format_args!("{:?}", input));
+        if (self.predicate)(&input) {
+            Err(self.error.clone())
+        } else {
+            Ok(input)
+        }
+    }
+}
+
+/// A [`std::io::Write`] that logs each line of the written bytes at a configured log level to [`log::log`].
+///
+/// Bytes are buffered until a newline is written, so a line spanning multiple writes is logged as a single log statement without its line ending.
+/// A trailing partial line is logged by [`Write::flush`] or when the writer is dropped.
+/// This writer is useful for integrating with APIs that report progress to a [`Write`] sink.
+pub struct LogWriter<'a> {
+    config: LogConfig<'a>,
+    buffer: Vec<u8>,
+}
+impl<'a> LogWriter<'a> {
+    /// Log input at the given log level
+    /// # Arguments
+    /// * `level` - The log level
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    pub fn new<S: Into<Cow<'a, str>>>(level: Level, prefix: S) -> Self {
+        Self {
+            config: LogConfig::new(level, prefix.into()),
+            buffer: Vec::new(),
+        }
+    }
+    /// Log as [`Level::Debug`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    pub fn debug<S: Into<Cow<'a, str>>>(prefix: S) -> Self {
+        Self::new(Level::Debug, prefix)
+    }
+    /// Log as [`Level::Error`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    pub fn error<S: Into<Cow<'a, str>>>(prefix: S) -> Self {
+        Self::new(Level::Error, prefix)
+    }
+    /// Log as [`Level::Info`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    pub fn info<S: Into<Cow<'a, str>>>(prefix: S) -> Self {
+        Self::new(Level::Info, prefix)
+    }
+    /// Log as [`Level::Trace`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    pub fn trace<S: Into<Cow<'a, str>>>(prefix: S) -> Self {
+        Self::new(Level::Trace, prefix)
+    }
+    /// Log as [`Level::Warn`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    pub fn warn<S: Into<Cow<'a, str>>>(prefix: S) -> Self {
+        Self::new(Level::Warn, prefix)
+    }
+    /// Log the given line, without its line ending
+    fn log_line(&self, line: &[u8]) {
+        let line = line.strip_suffix(b"\n").unwrap_or(line);
+        let line = line.strip_suffix(b"\r").unwrap_or(line);
+        self.config
+            .log(format_args!("{}", String::from_utf8_lossy(line)));
+    }
+}
+impl<'a> Write for LogWriter<'a> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.buffer.extend_from_slice(buf);
+        if let Some(end) = self.buffer.iter().rposition(|b| *b == b'\n') {
+            let complete: Vec<u8> = self.buffer.drain(..=end).collect();
+            for line in complete.split_inclusive(|b| *b == b'\n') {
+                self.log_line(line);
+            }
+        }
+        Ok(buf.len())
+    }
+    fn flush(&mut self) -> io::Result<()> {
+        if !self.buffer.is_empty() {
+            let partial = std::mem::take(&mut self.buffer);
+            self.log_line(&partial);
+        }
+        Ok(())
+    }
+}
+impl<'a> Drop for LogWriter<'a> {
+    fn drop(&mut self) {
+        let _ = self.flush();
+    }
+}
+
+/// A function that names the variant of enum input
+type VariantNamer<'a, T> = Box<dyn Fn(&T) -> &'static str + Send + Sync + 'a>;
+
+/// A [`sod::Service`] that logs the variant of enum input at a configured log level to [`log::log`], returning the input as output.
+///
+/// The variant is logged by name when configured with [`LogVariantService::with_namer`], and as its [`std::mem::Discriminant`] otherwise.
+/// This service is useful for logging which variant passed through a service chain without logging its contents.
+pub struct LogVariantService<'a, T> {
+    config: LogConfig<'a>,
+    namer: Option<VariantNamer<'a, T>>,
+}
+impl<'a, T> LogVariantService<'a, T> {
+    /// Log input at the given log level
+    /// # Arguments
+    /// * `level` - The log level
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    pub fn new<S: Into<Cow<'a, str>>>(level: Level, prefix: S) -> Self {
+        Self {
+            config: LogConfig::new(level, prefix.into()),
+            namer: None,
+        }
+    }
+    /// Log as [`Level::Debug`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    pub fn debug<S: Into<Cow<'a, str>>>(prefix: S) -> Self {
+        Self::new(Level::Debug, prefix)
+    }
+    /// Log as [`Level::Error`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    pub fn error<S: Into<Cow<'a, str>>>(prefix: S) -> Self {
+        Self::new(Level::Error, prefix)
+    }
+    /// Log as [`Level::Info`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    pub fn info<S: Into<Cow<'a, str>>>(prefix: S) -> Self {
+        Self::new(Level::Info, prefix)
+    }
+    /// Log as [`Level::Trace`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    pub fn trace<S: Into<Cow<'a, str>>>(prefix: S) -> Self {
+        Self::new(Level::Trace, prefix)
+    }
+    /// Log as [`Level::Warn`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    pub fn warn<S: Into<Cow<'a, str>>>(prefix: S) -> Self {
+        Self::new(Level::Warn, prefix)
+    }
+    /// Log the variant name produced by the given function instead of its discriminant
+    /// # Arguments
+    /// * `namer` - A function that names the variant of the input
+    pub fn with_namer<F: Fn(&T) -> &'static str + Send + Sync + 'a>(mut self, namer: F) -> Self {
+        self.namer = Some(Box::new(namer));
+        self
+    }
+}
+impl<'a, T> Service for LogVariantService<'a, T> {
+    type Input = T;
+    type Output = T;
+    type Error = ();
+    fn process(&self, input: T) -> Result<Self::Output, Self::Error> {
+        match &self.namer {
+            Some(namer) => self.config.log(format_args!("{}", namer(&input))),
+            None => self
+                .config
+                .log(format_args!("{:?}", std::mem::discriminant(&input))),
+        }
+        Ok(input)
+    }
+}
+
+/// A [`sod::Service`] that logs [`Display`] input at a base log level to [`log::log`], escalating to another log level when an equal value recurs more than a threshold number of times within a sliding window, returning the input as output.
+///
+/// This service is useful for noisy conditions that are only concerning when they persist, such as logging a retry as `debug` until it recurs often enough to warrant a `warn`.
+pub struct EscalatingLogDisplayService<'a, T> {
+    base: LogConfig<'a>,
+    escalated: LogConfig<'a>,
+    threshold: usize,
+    window: Duration,
+    occurrences: Mutex<Vec<(T, VecDeque<Instant>)>>,
+}
+impl<'a, T> EscalatingLogDisplayService<'a, T> {
+    /// Log input at the base log level, escalating recurring input to the escalated log level
+    /// # Arguments
+    /// * `base_level` - The log level of input that has not exceeded the threshold
+    /// * `escalated_level` - The log level of input that has exceeded the threshold
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `threshold` - The number of occurrences within the window that must be exceeded to escalate
+    /// * `window` - The sliding window that occurrences are counted within
+    pub fn new<S: Into<Cow<'a, str>>>(
+        base_level: Level,
+        escalated_level: Level,
+        prefix: S,
+        threshold: usize,
+        window: Duration,
+    ) -> Self {
+        let prefix = prefix.into();
+        Self {
+            base: LogConfig::new(base_level, prefix.clone()),
+            escalated: LogConfig::new(escalated_level, prefix),
+            threshold,
+            window,
+            occurrences: Mutex::new(Vec::new()),
+        }
+    }
+}
+impl<'a, T: PartialEq + Clone + Display> Service for EscalatingLogDisplayService<'a, T> {
+    type Input = T;
+    type Output = T;
+    type Error = ();
+    fn process(&self, input: T) -> Result<Self::Output, Self::Error> {
+        let now = Instant::now();
+        let mut occurrences = self
+            .occurrences
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        for (_, times) in occurrences.iter_mut() {
+            while times
+                .front()
+                .is_some_and(|time| now.duration_since(*time) > self.window)
+            {
+                times.pop_front();
+            }
+        }
+        occurrences.retain(|(_, times)| !times.is_empty());
+        let count = match occurrences.iter_mut().find(|(value, _)| *value == input) {
+            Some((_, times)) => {
+                times.push_back(now);
+                times.len()
+            }
+            None => {
+                occurrences.push((input.clone(), VecDeque::from([now])));
+                1
+            }
+        };
+        drop(occurrences);
+        let config = if count > self.threshold {
+            &self.escalated
+        } else {
+            &self.base
+        };
+        config.log(format_args!("{}", input));
+        Ok(input)
+    }
+}
+
+/// A [`sod::Service`] that logs [`Display`] input once at every log level from [`Level::Trace`] up to a configured maximum level to [`log::log`], returning the input as output.
+///
+/// This service is useful for diagnosing the filtering of a logger, since each enabled level produces its own log statement.
+pub struct LogEscalateService<'a, T> {
+    configs: Vec<LogConfig<'a>>,
+    _phantom: PhantomData<fn(T)>,
+}
+impl<'a, T> LogEscalateService<'a, T> {
+    /// Log input at every log level up to the given maximum level
+    /// # Arguments
+    /// * `max_level` - The most severe log level to log at
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    pub fn new<S: Into<Cow<'a, str>>>(max_level: Level, prefix: S) -> Self {
+        let prefix = prefix.into();
+        Self {
+            configs: [
+                Level::Trace,
+                Level::Debug,
+                Level::Info,
+                Level::Warn,
+                Level::Error,
+            ]
+            .into_iter()
+            .filter(|level| *level >= max_level)
+            .map(|level| LogConfig::new(level, prefix.clone()))
+            .collect(),
+            _phantom: PhantomData,
+        }
+    }
+}
+impl<'a, T: Display> Service for LogEscalateService<'a, T> {
+    type Input = T;
+    type Output = T;
+    type Error = ();
+    fn process(&self, input: T) -> Result<Self::Output, Self::Error> {
+        for config in &self.configs {
+            config.log(format_args!("{}", input));
+        }
+        Ok(input)
+    }
+}
+
+/// A [`sod::Service`] that counts input and logs the observed throughput at a configured log level to [`log::log`] when an interval has elapsed, returning the input as output.
+///
+/// The throughput is logged in the form of `{prefix}{rate} msg/s`, counting the input processed since the last log statement, including the input that triggers it.
+/// Use [`LogRateService::with_format`] to log it in the form of `{prefix}rate={rate}/s` instead.
+/// The interval is checked each time input is processed, so nothing is logged while no input is processed.
+pub struct LogRateService<'a, T> {
+    config: LogConfig<'a>,
+    throughput: Throughput,
+    format: RateFormat,
+    _phantom: PhantomData<fn(T)>,
+}
+impl<'a, T> LogRateService<'a, T> {
+    /// Log throughput at the given log level
+    /// # Arguments
+    /// * `level` - The log level
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `interval` - The minimum duration between log statements
+    pub fn new<S: Into<Cow<'a, str>>>(level: Level, prefix: S, interval: Duration) -> Self {
+        Self {
+            config: LogConfig::new(level, prefix.into()),
+            throughput: Throughput::new(interval),
+            format: RateFormat::Messages,
+            _phantom: PhantomData,
+        }
+    }
+    /// Log as [`Level::Debug`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `interval` - The minimum duration between log statements
+    pub fn debug<S: Into<Cow<'a, str>>>(prefix: S, interval: Duration) -> Self {
+        Self::new(Level::Debug, prefix, interval)
+    }
+    /// Log as [`Level::Error`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `interval` - The minimum duration between log statements
+    pub fn error<S: Into<Cow<'a, str>>>(prefix: S, interval: Duration) -> Self {
+        Self::new(Level::Error, prefix, interval)
+    }
+    /// Log as [`Level::Info`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `interval` - The minimum duration between log statements
+    pub fn info<S: Into<Cow<'a, str>>>(prefix: S, interval: Duration) -> Self {
+        Self::new(Level::Info, prefix, interval)
+    }
+    /// Log as [`Level::Trace`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `interval` - The minimum duration between log statements
+    pub fn trace<S: Into<Cow<'a, str>>>(prefix: S, interval: Duration) -> Self {
+        Self::new(Level::Trace, prefix, interval)
+    }
+    /// Log as [`Level::Warn`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `interval` - The minimum duration between log statements
+    pub fn warn<S: Into<Cow<'a, str>>>(prefix: S, interval: Duration) -> Self {
+        Self::new(Level::Warn, prefix, interval)
+    }
+    /// Set the form that the throughput is logged in
+    /// # Arguments
+    /// * `format` - The format
+    pub fn with_format(mut self, format: RateFormat) -> Self {
+        self.format = format;
+        self
+    }
+}
+impl<'a, T> Service for LogRateService<'a, T> {
+    type Input = T;
+    type Output = T;
+    type Error = ();
+    fn process(&self, input: T) -> Result<Self::Output, Self::Error> {
+        if let Some(rate) = self.throughput.count() {
+            match self.format {
+                RateFormat::Messages => self.config.log(format_args!("{:.0} msg/s", rate)),
+                RateFormat::KeyValue => self.config.log(format_args!("rate={:.0}/s", rate)),
+            }
+        }
+        Ok(input)
+    }
+}
+
+/// The form that [`LogRateService`] logs throughput in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum RateFormat {
+    /// Messages per second, such as `50 msg/s`
+    #[default]
+    Messages,
+    /// A key-value of the rate per second, such as `rate=50/s`
+    KeyValue,
+}
+
+/// Counts input within a window that is reset each time an interval has elapsed
+struct Throughput {
+    interval: Duration,
+    window: Mutex<(Instant, u64)>,
+}
+impl Throughput {
+    fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            window: Mutex::new((Instant::now(), 0)),
+        }
+    }
+    /// Count a single input, returning the rate per second of the window when the interval has elapsed
+    fn count(&self) -> Option<f64> {
+        let now = Instant::now();
+        let mut window = self.window.lock().unwrap_or_else(PoisonError::into_inner);
+        let (start, count) = &mut *window;
+        *count += 1;
+        let elapsed = now.duration_since(*start);
+        if elapsed >= self.interval {
+            let rate = *count as f64 / elapsed.as_secs_f64();
+            *window = (now, 0);
+            Some(rate)
+        } else {
+            None
+        }
+    }
+}
+
+/// A [`sod::Service`] that logs [`Display`] input at the log level it is paired with to [`log::log`], returning the input without the log level as output.
+///
+/// This service is useful when a prior stage of a service chain computes the severity of its output, such as a validation stage that produces `(Level::Warn, message)`.
+pub struct LogPairedLevelService<'a, T> {
+    configs: [LogConfig<'a>; 5],
+    _phantom: PhantomData<fn(T)>,
+}
+impl<'a, T> LogPairedLevelService<'a, T> {
+    /// Log input at the log level it is paired with
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    pub fn new<S: Into<Cow<'a, str>>>(prefix: S) -> Self {
+        let prefix: Cow<'a, str> = prefix.into();
+        Self {
+            configs: [
+                Level::Error,
+                Level::Warn,
+                Level::Info,
+                Level::Debug,
+                Level::Trace,
+            ]
+            .map(|level| LogConfig::new(level, prefix.clone())),
+            _phantom: PhantomData,
+        }
+    }
+}
+impl<'a, T: Display> Service for LogPairedLevelService<'a, T> {
+    type Input = (Level, T);
+    type Output = T;
+    type Error = ();
+    fn process(&self, (level, input): (Level, T)) -> Result<Self::Output, Self::Error> {
+        self.configs[level as usize - 1].log(format_args!("{}", input));
+        Ok(input)
+    }
+}
+
+/// A [`sod::Service`] that records numeric input, such as latencies, in a bucketed histogram and logs estimated percentiles at a configured log level to [`log::log`] after every configured number of inputs, returning the input as output.
+///
+/// Percentiles are logged in the form of `{prefix}p50={p50} p95={p95} p99={p99}`, where each estimate is the upper bound of the bucket containing the percentile,
+/// or the largest recorded value for input above the last bound. The histogram is reset after each log statement.
+pub struct HistogramLogService<'a, T> {
+    config: LogConfig<'a>,
+    bounds: Vec<f64>,
+    report_every: u64,
+    histogram: Mutex<Histogram>,
+    _phantom: PhantomData<fn(T)>,
+}
+impl<'a, T> HistogramLogService<'a, T> {
+    /// Log percentiles at the given log level
+    /// # Arguments
+    /// * `level` - The log level
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `bounds` - The ascending upper bounds of the histogram buckets
+    /// * `report_every` - The number of inputs to record between log statements
+    pub fn new<S: Into<Cow<'a, str>>>(
+        level: Level,
+        prefix: S,
+        bounds: Vec<f64>,
+        report_every: u64,
+    ) -> Self {
+        Self {
+            config: LogConfig::new(level, prefix.into()),
+            histogram: Mutex::new(Histogram::new(bounds.len())),
+            bounds,
+            report_every,
+            _phantom: PhantomData,
+        }
+    }
+    /// Log as [`Level::Debug`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `bounds` - The ascending upper bounds of the histogram buckets
+    /// * `report_every` - The number of inputs to record between log statements
+    pub fn debug<S: Into<Cow<'a, str>>>(prefix: S, bounds: Vec<f64>, report_every: u64) -> Self {
+        Self::new(Level::Debug, prefix, bounds, report_every)
+    }
+    /// Log as [`Level::Error`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `bounds` - The ascending upper bounds of the histogram buckets
+    /// * `report_every` - The number of inputs to record between log statements
+    pub fn error<S: Into<Cow<'a, str>>>(prefix: S, bounds: Vec<f64>, report_every: u64) -> Self {
+        Self::new(Level::Error, prefix, bounds, report_every)
+    }
+    /// Log as [`Level::Info`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `bounds` - The ascending upper bounds of the histogram buckets
+    /// * `report_every` - The number of inputs to record between log statements
+    pub fn info<S: Into<Cow<'a, str>>>(prefix: S, bounds: Vec<f64>, report_every: u64) -> Self {
+        Self::new(Level::Info, prefix, bounds, report_every)
+    }
+    /// Log as [`Level::Trace`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `bounds` - The ascending upper bounds of the histogram buckets
+    /// * `report_every` - The number of inputs to record between log statements
+    pub fn trace<S: Into<Cow<'a, str>>>(prefix: S, bounds: Vec<f64>, report_every: u64) -> Self {
+        Self::new(Level::Trace, prefix, bounds, report_every)
+    }
+    /// Log as [`Level::Warn`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `bounds` - The ascending upper bounds of the histogram buckets
+    /// * `report_every` - The number of inputs to record between log statements
+    pub fn warn<S: Into<Cow<'a, str>>>(prefix: S, bounds: Vec<f64>, report_every: u64) -> Self {
+        Self::new(Level::Warn, prefix, bounds, report_every)
+    }
+}
+impl<'a, T: Into<f64> + Copy> Service for HistogramLogService<'a, T> {
+    type Input = T;
+    type Output = T;
+    type Error = ();
+    fn process(&self, input: T) -> Result<Self::Output, Self::Error> {
+        let value = input.into();
+        let mut histogram = self
+            .histogram
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        let bucket = self.bounds.partition_point(|bound| *bound < value);
+        histogram.counts[bucket] += 1;
+        histogram.total += 1;
+        histogram.max = histogram.max.max(value);
+        if histogram.total >= self.report_every {
+            self.config.log(format_args!(
+                "p50={} p95={} p99={}",
+                histogram.percentile(&self.bounds, 0.50),
+                histogram.percentile(&self.bounds, 0.95),
+                histogram.percentile(&self.bounds, 0.99)
+            ));
+            *histogram = Histogram::new(self.bounds.len());
+        }
+        Ok(input)
+    }
+}
+
+/// The bucket counts recorded by [`HistogramLogService`], with a final bucket for values above the last bound
+struct Histogram {
+    counts: Vec<u64>,
+    total: u64,
+    max: f64,
+}
+impl Histogram {
+    fn new(bounds: usize) -> Self {
+        Self {
+            counts: vec![0; bounds + 1],
+            total: 0,
+            max: f64::MIN,
+        }
+    }
+    /// Estimate the given percentile as the upper bound of the bucket that contains it
+    fn percentile(&self, bounds: &[f64], percentile: f64) -> f64 {
+        let rank = (percentile * self.total as f64).ceil().max(1.0) as u64;
+        let mut cumulative = 0;
+        for (bucket, count) in self.counts.iter().enumerate() {
+            cumulative += count;
+            if cumulative >= rank {
+                return bounds.get(bucket).copied().unwrap_or(self.max);
+            }
+        }
+        self.max
+    }
+}
+
+/// A [`sod::Service`] that logs the [`Display`] elements of tuple input with labels at a configured log level to [`log::log`], returning the input as output.
+///
+/// Tuples of 2 through 4 elements are logged in the form of `{prefix}(key=foo, value=42)`, where an element without a label is labeled by its index.
+/// The tuple type is inferred from a service chain, and is otherwise annotated, such as `LogTupleService::<(_, _)>::info("", ["key", "value"])`.
+pub struct LogTupleService<'a, T> {
+    config: LogConfig<'a>,
+    labels: Vec<Cow<'a, str>>,
+    _phantom: PhantomData<fn(T)>,
+}
+impl<'a, T> LogTupleService<'a, T> {
+    /// Log input at the given log level
+    /// # Arguments
+    /// * `level` - The log level
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `labels` - The labels of the tuple elements, in order
+    pub fn new<S: Into<Cow<'a, str>>, L: IntoIterator<Item = I>, I: Into<Cow<'a, str>>>(
+        level: Level,
+        prefix: S,
+        labels: L,
+    ) -> Self {
+        Self {
+            config: LogConfig::new(level, prefix.into()),
+            labels: labels.into_iter().map(Into::into).collect(),
+            _phantom: PhantomData,
+        }
+    }
+    /// Log as [`Level::Debug`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `labels` - The labels of the tuple elements, in order
+    pub fn debug<S: Into<Cow<'a, str>>, L: IntoIterator<Item = I>, I: Into<Cow<'a, str>>>(
+        prefix: S,
+        labels: L,
+    ) -> Self {
+        Self::new(Level::Debug, prefix, labels)
+    }
+    /// Log as [`Level::Error`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `labels` - The labels of the tuple elements, in order
+    pub fn error<S: Into<Cow<'a, str>>, L: IntoIterator<Item = I>, I: Into<Cow<'a, str>>>(
+        prefix: S,
+        labels: L,
+    ) -> Self {
+        Self::new(Level::Error, prefix, labels)
+    }
+    /// Log as [`Level::Info`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `labels` - The labels of the tuple elements, in order
+    pub fn info<S: Into<Cow<'a, str>>, L: IntoIterator<Item = I>, I: Into<Cow<'a, str>>>(
+        prefix: S,
+        labels: L,
+    ) -> Self {
+        Self::new(Level::Info, prefix, labels)
+    }
+    /// Log as [`Level::Trace`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `labels` - The labels of the tuple elements, in order
+    pub fn trace<S: Into<Cow<'a, str>>, L: IntoIterator<Item = I>, I: Into<Cow<'a, str>>>(
+        prefix: S,
+        labels: L,
+    ) -> Self {
+        Self::new(Level::Trace, prefix, labels)
+    }
+    /// Log as [`Level::Warn`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `labels` - The labels of the tuple elements, in order
+    pub fn warn<S: Into<Cow<'a, str>>, L: IntoIterator<Item = I>, I: Into<Cow<'a, str>>>(
+        prefix: S,
+        labels: L,
+    ) -> Self {
+        Self::new(Level::Warn, prefix, labels)
+    }
+    /// Get the label of the element at the given index
+    fn label(&self, index: usize) -> Labeled<'_> {
+        Labeled(self.labels.get(index).map(|label| label.as_ref()), index)
+    }
+}
+
+/// Formats a tuple element label, or its index when it has no label
+struct Labeled<'l>(Option<&'l str>, usize);
+impl<'l> Display for Labeled<'l> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            Some(label) => f.write_str(label),
+            None => write!(f, "{}", self.1),
+        }
+    }
+}
+
+macro_rules! impl_log_tuple_service {
+    ($($element:ident $index:tt),+) => {
+        impl<'a, $($element: Display),+> Service for LogTupleService<'a, ($($element,)+)> {
+            type Input = ($($element,)+);
+            type Output = ($($element,)+);
+            type Error = ();
+            fn process(&self, input: Self::Input) -> Result<Self::Output, Self::Error> {
+                self.config.log(format_args!(
+                    concat!("(", impl_log_tuple_service!(@format $($index)+), ")"),
+                    $(self.label($index), input.$index),+
+                ));
+                Ok(input)
+            }
+        }
+    };
+    (@format $first:tt $($rest:tt)*) => {
+        concat!("{}={}" $(, impl_log_tuple_service!(@separator $rest))*)
+    };
+    (@separator $index:tt) => {
+        ", {}={}"
+    };
+}
+impl_log_tuple_service!(A 0, B 1);
+impl_log_tuple_service!(A 0, B 1, C 2);
+impl_log_tuple_service!(A 0, B 1, C 2, D 3);
+
+/// A [`sod::Service`] that logs when input crosses a threshold at a configured log level to [`log::log`], returning the input as output.
+///
+/// Crossings are logged in the form of `{prefix}crossed above {threshold}` or `{prefix}crossed below {threshold}`, where input equal to the threshold is not above it.
+/// The first input only establishes which side of the threshold input is on, so nothing is logged until input changes sides.
+pub struct ThresholdCrossingLogService<'a, T> {
+    config: LogConfig<'a>,
+    threshold: T,
+    above: Mutex<Option<bool>>,
+}
+impl<'a, T> ThresholdCrossingLogService<'a, T> {
+    /// Log crossings at the given log level
+    /// # Arguments
+    /// * `level` - The log level
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `threshold` - The threshold that input must cross to be logged
+    pub fn new<S: Into<Cow<'a, str>>>(level: Level, prefix: S, threshold: T) -> Self {
+        Self {
+            config: LogConfig::new(level, prefix.into()),
+            threshold,
+            above: Mutex::new(None),
+        }
+    }
+    /// Log as [`Level::Debug`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `threshold` - The threshold that input must cross to be logged
+    pub fn debug<S: Into<Cow<'a, str>>>(prefix: S, threshold: T) -> Self {
+        Self::new(Level::Debug, prefix, threshold)
+    }
+    /// Log as [`Level::Error`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `threshold` - The threshold that input must cross to be logged
+    pub fn error<S: Into<Cow<'a, str>>>(prefix: S, threshold: T) -> Self {
+        Self::new(Level::Error, prefix, threshold)
+    }
+    /// Log as [`Level::Info`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `threshold` - The threshold that input must cross to be logged
+    pub fn info<S: Into<Cow<'a, str>>>(prefix: S, threshold: T) -> Self {
+        Self::new(Level::Info, prefix, threshold)
+    }
+    /// Log as [`Level::Trace`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `threshold` - The threshold that input must cross to be logged
+    pub fn trace<S: Into<Cow<'a, str>>>(prefix: S, threshold: T) -> Self {
+        Self::new(Level::Trace, prefix, threshold)
+    }
+    /// Log as [`Level::Warn`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `threshold` - The threshold that input must cross to be logged
+    pub fn warn<S: Into<Cow<'a, str>>>(prefix: S, threshold: T) -> Self {
+        Self::new(Level::Warn, prefix, threshold)
+    }
+}
+impl<'a, T: PartialOrd + Copy + Display> Service for ThresholdCrossingLogService<'a, T> {
+    type Input = T;
+    type Output = T;
+    type Error = ();
+    fn process(&self, input: T) -> Result<Self::Output, Self::Error> {
+        let above = input > self.threshold;
+        let mut last = self.above.lock().unwrap_or_else(PoisonError::into_inner);
+        if last.is_some_and(|last| last != above) {
+            let direction = if above { "above" } else { "below" };
+            self.config
+                .log(format_args!("crossed {} {}", direction, self.threshold));
+        }
+        *last = Some(above);
+        Ok(input)
+    }
+}
+
+/// A function that receives the log level and formatted log statement of a [`LogSinkService`]
+pub type LogSink = Arc<dyn Fn(Level, &str) + Send + Sync>;
+
+/// A [`sod::Service`] that formats [`Display`] input and passes it with a configured log level to a [`LogSink`] function, returning the input as output.
+///
+/// Unlike the other services, this service does not log to [`log::log`], which allows log statements to be routed anywhere, such as to `tracing` or a test collector.
+pub struct LogSinkService<'a, T> {
+    level: Level,
+    prefix: Cow<'a, str>,
+    sink: LogSink,
+    _phantom: PhantomData<fn(T)>,
+}
+impl<'a, T> LogSinkService<'a, T> {
+    /// Log input at the given log level to the given sink
+    /// # Arguments
+    /// * `level` - The log level
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `sink` - The function that receives the log level and formatted log statement
+    pub fn new<S: Into<Cow<'a, str>>>(level: Level, prefix: S, sink: LogSink) -> Self {
+        Self {
+            level,
+            prefix: prefix.into(),
+            sink,
+            _phantom: PhantomData,
+        }
+    }
+    /// Log as [`Level::Debug`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `sink` - The function that receives the log level and formatted log statement
+    pub fn debug<S: Into<Cow<'a, str>>>(prefix: S, sink: LogSink) -> Self {
+        Self::new(Level::Debug, prefix, sink)
+    }
+    /// Log as [`Level::Error`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `sink` - The function that receives the log level and formatted log statement
+    pub fn error<S: Into<Cow<'a, str>>>(prefix: S, sink: LogSink) -> Self {
+        Self::new(Level::Error, prefix, sink)
+    }
+    /// Log as [`Level::Info`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `sink` - The function that receives the log level and formatted log statement
+    pub fn info<S: Into<Cow<'a, str>>>(prefix: S, sink: LogSink) -> Self {
+        Self::new(Level::Info, prefix, sink)
+    }
+    /// Log as [`Level::Trace`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `sink` - The function that receives the log level and formatted log statement
+    pub fn trace<S: Into<Cow<'a, str>>>(prefix: S, sink: LogSink) -> Self {
+        Self::new(Level::Trace, prefix, sink)
+    }
+    /// Log as [`Level::Warn`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `sink` - The function that receives the log level and formatted log statement
+    pub fn warn<S: Into<Cow<'a, str>>>(prefix: S, sink: LogSink) -> Self {
+        Self::new(Level::Warn, prefix, sink)
+    }
+}
+impl<'a, T: Display> Service for LogSinkService<'a, T> {
+    type Input = T;
+    type Output = T;
+    type Error = ();
+    fn process(&self, input: T) -> Result<Self::Output, Self::Error> {
+        (self.sink)(self.level, &format!("{}{}", self.prefix, input));
+        Ok(input)
+    }
+}
+
+/// A [`sod::Service`] that counts how often optional input is `Some` and logs the fill rate at a configured log level to [`log::log`] after every window of inputs, returning the input as output.
+///
+/// The fill rate is logged in the form of `{prefix}fill={percent}%`, and the counts are reset after each log statement.
+/// This service is useful for deciding whether an optional stage of a service chain is worthwhile.
+pub struct OptionFillRateLogService<'a, T> {
+    config: LogConfig<'a>,
+    window: u64,
+    counts: Mutex<(u64, u64)>,
+    _phantom: PhantomData<fn(T)>,
+}
+impl<'a, T> OptionFillRateLogService<'a, T> {
+    /// Log the fill rate at the given log level
+    /// # Arguments
+    /// * `level` - The log level
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `window` - The number of inputs to count between log statements
+    pub fn new<S: Into<Cow<'a, str>>>(level: Level, prefix: S, window: u64) -> Self {
+        Self {
+            config: LogConfig::new(level, prefix.into()),
+            window,
+            counts: Mutex::new((0, 0)),
+            _phantom: PhantomData,
+        }
+    }
+    /// Log as [`Level::Debug`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `window` - The number of inputs to count between log statements
+    pub fn debug<S: Into<Cow<'a, str>>>(prefix: S, window: u64) -> Self {
+        Self::new(Level::Debug, prefix, window)
+    }
+    /// Log as [`Level::Error`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `window` - The number of inputs to count between log statements
+    pub fn error<S: Into<Cow<'a, str>>>(prefix: S, window: u64) -> Self {
+        Self::new(Level::Error, prefix, window)
+    }
+    /// Log as [`Level::Info`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `window` - The number of inputs to count between log statements
+    pub fn info<S: Into<Cow<'a, str>>>(prefix: S, window: u64) -> Self {
+        Self::new(Level::Info, prefix, window)
+    }
+    /// Log as [`Level::Trace`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `window` - The number of inputs to count between log statements
+    pub fn trace<S: Into<Cow<'a, str>>>(prefix: S, window: u64) -> Self {
+        Self::new(Level::Trace, prefix, window)
+    }
+    /// Log as [`Level::Warn`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `window` - The number of inputs to count between log statements
+    pub fn warn<S: Into<Cow<'a, str>>>(prefix: S, window: u64) -> Self {
+        Self::new(Level::Warn, prefix, window)
+    }
+}
+impl<'a, T> Service for OptionFillRateLogService<'a, T> {
+    type Input = Option<T>;
+    type Output = Option<T>;
+    type Error = ();
+    fn process(&self, input: Option<T>) -> Result<Self::Output, Self::Error> {
+        let mut counts = self.counts.lock().unwrap_or_else(PoisonError::into_inner);
+        let (some, total) = &mut *counts;
+        if input.is_some() {
+            *some += 1;
+        }
+        *total += 1;
+        if *total >= self.window {
+            let percent = *some as f64 * 100.0 / *total as f64;
+            *counts = (0, 0);
+            self.config.log(format_args!("fill={:.0}%", percent));
+        }
+        Ok(input)
+    }
+}
+
+/// A [`sod::Service`] that logs a projection of the `Ok` value of [`Result`] input at a configured log level to [`log::log`], returning the input as output.
+///
+/// Nothing is logged for `Err` input. This service is useful for logging a single field of a successful response, such as its status.
+pub struct LogOkFieldService<'a, T, E, F> {
+    config: LogConfig<'a>,
+    project: F,
+    _phantom: PhantomData<fn(T, E)>,
+}
+impl<'a, T, E, F: Fn(&T) -> String> LogOkFieldService<'a, T, E, F> {
+    /// Log input at the given log level
+    /// # Arguments
+    /// * `level` - The log level
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `project` - A function that projects the `Ok` value to the logged string
+    pub fn new<S: Into<Cow<'a, str>>>(level: Level, prefix: S, project: F) -> Self {
+        Self {
+            config: LogConfig::new(level, prefix.into()),
+            project,
+            _phantom: PhantomData,
+        }
+    }
+    /// Log as [`Level::Debug`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `project` - A function that projects the `Ok` value to the logged string
+    pub fn debug<S: Into<Cow<'a, str>>>(prefix: S, project: F) -> Self {
+        Self::new(Level::Debug, prefix, project)
+    }
+    /// Log as [`Level::Error`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `project` - A function that projects the `Ok` value to the logged string
+    pub fn error<S: Into<Cow<'a, str>>>(prefix: S, project: F) -> Self {
+        Self::new(Level::Error, prefix, project)
+    }
+    /// Log as [`Level::Info`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `project` - A function that projects the `Ok` value to the logged string
+    pub fn info<S: Into<Cow<'a, str>>>(prefix: S, project: F) -> Self {
+        Self::new(Level::Info, prefix, project)
+    }
+    /// Log as [`Level::Trace`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `project` - A function that projects the `Ok` value to the logged string
+    pub fn trace<S: Into<Cow<'a, str>>>(prefix: S, project: F) -> Self {
+        Self::new(Level::Trace, prefix, project)
+    }
+    /// Log as [`Level::Warn`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `project` - A function that projects the `Ok` value to the logged string
+    pub fn warn<S: Into<Cow<'a, str>>>(prefix: S, project: F) -> Self {
+        Self::new(Level::Warn, prefix, project)
+    }
+}
+impl<'a, T, E, F: Fn(&T) -> String> Service for LogOkFieldService<'a, T, E, F> {
+    type Input = Result<T, E>;
+    type Output = Result<T, E>;
+    type Error = ();
+    fn process(&self, input: Result<T, E>) -> Result<Self::Output, Self::Error> {
+        if let Ok(value) = &input {
+            if self.config.is_enabled() {
+                self.config.log(format_args!("{}", (self.project)(value)));
+            }
+        }
+        Ok(input)
+    }
+}
+
+thread_local! {
+    /// The buffer reused to format input that is transformed before it is logged, which avoids an allocation per log statement
+    static FORMAT_BUFFER: RefCell<String> = const { RefCell::new(String::new()) };
+}
+
+/// The capacity above which [`FORMAT_BUFFER`] is released after use, so a single large input does not hold memory for the life of the thread
+const MAX_RETAINED_BUFFER: usize = 4096;
+
+/// The id assigned to the next constructed [`LogConfig`]
+static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+
+/// A type-erased function that renders input for a [`RenderLogService`], which allows renderers to be selected at runtime and stored uniformly
+pub type Renderer<'a, T> = Box<dyn Fn(&T) -> String + Send + Sync + 'a>;
+
+/// A [`sod::Service`] that logs input rendered by a [`Renderer`] at a configured log level to [`log::log`], returning the input as output.
+///
+/// The renderer may be replaced with [`RenderLogService::set_renderer`] while the service is in use,
+/// which costs an uncontended read lock per processed input.
+pub struct RenderLogService<'a, T> {
+    config: LogConfig<'a>,
+    renderer: RwLock<Renderer<'a, T>>,
+}
+impl<'a, T> RenderLogService<'a, T> {
+    /// Log input at the given log level
+    /// # Arguments
+    /// * `level` - The log level
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `renderer` - The function that renders input
+    pub fn new<S: Into<Cow<'a, str>>>(level: Level, prefix: S, renderer: Renderer<'a, T>) -> Self {
+        Self {
+            config: LogConfig::new(level, prefix.into()),
+            renderer: RwLock::new(renderer),
+        }
+    }
+    /// Log as [`Level::Debug`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `renderer` - The function that renders input
+    pub fn debug<S: Into<Cow<'a, str>>>(prefix: S, renderer: Renderer<'a, T>) -> Self {
+        Self::new(Level::Debug, prefix, renderer)
+    }
+    /// Log as [`Level::Error`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `renderer` - The function that renders input
+    pub fn error<S: Into<Cow<'a, str>>>(prefix: S, renderer: Renderer<'a, T>) -> Self {
+        Self::new(Level::Error, prefix, renderer)
+    }
+    /// Log as [`Level::Info`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `renderer` - The function that renders input
+    pub fn info<S: Into<Cow<'a, str>>>(prefix: S, renderer: Renderer<'a, T>) -> Self {
+        Self::new(Level::Info, prefix, renderer)
+    }
+    /// Log as [`Level::Trace`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `renderer` - The function that renders input
+    pub fn trace<S: Into<Cow<'a, str>>>(prefix: S, renderer: Renderer<'a, T>) -> Self {
+        Self::new(Level::Trace, prefix, renderer)
+    }
+    /// Log as [`Level::Warn`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `renderer` - The function that renders input
+    pub fn warn<S: Into<Cow<'a, str>>>(prefix: S, renderer: Renderer<'a, T>) -> Self {
+        Self::new(Level::Warn, prefix, renderer)
+    }
+    /// Replace the renderer, which applies to subsequently processed input
+    /// # Arguments
+    /// * `renderer` - The function that renders input
+    pub fn set_renderer(&self, renderer: Renderer<'a, T>) {
+        *self
+            .renderer
+            .write()
+            .unwrap_or_else(PoisonError::into_inner) = renderer;
+    }
+}
+impl<'a, T> Service for RenderLogService<'a, T> {
+    type Input = T;
+    type Output = T;
+    type Error = ();
+    fn process(&self, input: T) -> Result<Self::Output, Self::Error> {
+        if self.config.is_enabled() {
+            let renderer = self.renderer.read().unwrap_or_else(PoisonError::into_inner);
+            self.config.log(format_args!("{}", renderer(&input)));
+        }
+        Ok(input)
+    }
+}
+
+/// A [`sod::Service`] that logs [`Display`] input at a configured log level to [`log::log`], returning the input and a clone of it as output.
+///
+/// This service is useful for fanning out a service chain into two branches, such as one that consumes the input and one that records it.
+pub struct LogCloneTeeService<'a, T> {
+    config: LogConfig<'a>,
+    _phantom: PhantomData<fn(T)>,
+}
+impl<'a, T> LogCloneTeeService<'a, T> {
+    /// Log input at the given log level
+    /// # Arguments
+    /// * `level` - The log level
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    pub fn new<S: Into<Cow<'a, str>>>(level: Level, prefix: S) -> Self {
+        Self {
+            config: LogConfig::new(level, prefix.into()),
+            _phantom: PhantomData,
+        }
+    }
+    /// Log as [`Level::Debug`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    pub fn debug<S: Into<Cow<'a, str>>>(prefix: S) -> Self {
+        Self::new(Level::Debug, prefix)
+    }
+    /// Log as [`Level::Error`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    pub fn error<S: Into<Cow<'a, str>>>(prefix: S) -> Self {
+        Self::new(Level::Error, prefix)
+    }
+    /// Log as [`Level::Info`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    pub fn info<S: Into<Cow<'a, str>>>(prefix: S) -> Self {
+        Self::new(Level::Info, prefix)
+    }
+    /// Log as [`Level::Trace`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    pub fn trace<S: Into<Cow<'a, str>>>(prefix: S) -> Self {
+        Self::new(Level::Trace, prefix)
+    }
+    /// Log as [`Level::Warn`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    pub fn warn<S: Into<Cow<'a, str>>>(prefix: S) -> Self {
+        Self::new(Level::Warn, prefix)
+    }
+}
+impl<'a, T: Display + Clone> Service for LogCloneTeeService<'a, T> {
+    type Input = T;
+    type Output = (T, T);
+    type Error = ();
+    fn process(&self, input: T) -> Result<Self::Output, Self::Error> {
+        self.config.log(format_args!("{}", input));
+        Ok((input.clone(), input))
+    }
+}
+
+/// A [`sod::Service`] that logs the [`Display`] error of [`Result`] input at a configured log level to [`log::log`] when it differs from the last logged error, returning the input as output.
+///
+/// Repeated identical errors are suppressed until a different error appears, and `Ok` input is never logged and does not reset the last logged error.
+/// This service is useful for service chains that repeatedly encounter the same error, such as a connection that stays refused.
+pub struct LogDistinctErrorService<'a, T, E> {
+    config: LogConfig<'a>,
+    last: Mutex<Option<E>>,
+    _phantom: PhantomData<fn(T)>,
+}
+impl<'a, T, E> LogDistinctErrorService<'a, T, E> {
+    /// Log input at the given log level
+    /// # Arguments
+    /// * `level` - The log level
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    pub fn new<S: Into<Cow<'a, str>>>(level: Level, prefix: S) -> Self {
+        Self {
+            config: LogConfig::new(level, prefix.into()),
+            last: Mutex::new(None),
+            _phantom: PhantomData,
+        }
+    }
+    /// Log as [`Level::Debug`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    pub fn debug<S: Into<Cow<'a, str>>>(prefix: S) -> Self {
+        Self::new(Level::Debug, prefix)
+    }
+    /// Log as [`Level::Error`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    pub fn error<S: Into<Cow<'a, str>>>(prefix: S) -> Self {
+        Self::new(Level::Error, prefix)
+    }
+    /// Log as [`Level::Info`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    pub fn info<S: Into<Cow<'a, str>>>(prefix: S) -> Self {
+        Self::new(Level::Info, prefix)
+    }
+    /// Log as [`Level::Trace`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    pub fn trace<S: Into<Cow<'a, str>>>(prefix: S) -> Self {
+        Self::new(Level::Trace, prefix)
+    }
+    /// Log as [`Level::Warn`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    pub fn warn<S: Into<Cow<'a, str>>>(prefix: S) -> Self {
+        Self::new(Level::Warn, prefix)
+    }
+}
+impl<'a, T, E: PartialEq + Display + Clone> Service for LogDistinctErrorService<'a, T, E> {
+    type Input = Result<T, E>;
+    type Output = Result<T, E>;
+    type Error = ();
+    fn process(&self, input: Result<T, E>) -> Result<Self::Output, Self::Error> {
+        if let Err(err) = &input {
+            let mut last = self.last.lock().unwrap_or_else(PoisonError::into_inner);
+            if last.as_ref() != Some(err) {
+                self.config.log(format_args!("{}", err));
+                *last = Some(err.clone());
+            }
+        }
+        Ok(input)
+    }
+}
+
+/// A [`sod::Service`] that logs [`Display`] input tagged with an epoch number at a configured log level to [`log::log`], returning the input as output.
+///
+/// Each log statement is in the form of `{prefix}[epoch {epoch}] {input}`, where the epoch starts at `0` and is incremented by the caller through [`EpochLogDisplayService::epoch_handle`],
+/// such as between batches.
+pub struct EpochLogDisplayService<'a, T> {
+    config: LogConfig<'a>,
+    epoch: Arc<AtomicU64>,
+    _phantom: PhantomData<fn(T)>,
+}
+impl<'a, T> EpochLogDisplayService<'a, T> {
+    /// Log input at the given log level
+    /// # Arguments
+    /// * `level` - The log level
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    pub fn new<S: Into<Cow<'a, str>>>(level: Level, prefix: S) -> Self {
+        Self {
+            config: LogConfig::new(level, prefix.into()),
+            epoch: Arc::new(AtomicU64::new(0)),
+            _phantom: PhantomData,
+        }
+    }
+    /// Log as [`Level::Debug`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    pub fn debug<S: Into<Cow<'a, str>>>(prefix: S) -> Self {
+        Self::new(Level::Debug, prefix)
+    }
+    /// Log as [`Level::Error`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    pub fn error<S: Into<Cow<'a, str>>>(prefix: S) -> Self {
+        Self::new(Level::Error, prefix)
+    }
+    /// Log as [`Level::Info`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    pub fn info<S: Into<Cow<'a, str>>>(prefix: S) -> Self {
+        Self::new(Level::Info, prefix)
+    }
+    /// Log as [`Level::Trace`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    pub fn trace<S: Into<Cow<'a, str>>>(prefix: S) -> Self {
+        Self::new(Level::Trace, prefix)
+    }
+    /// Log as [`Level::Warn`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    pub fn warn<S: Into<Cow<'a, str>>>(prefix: S) -> Self {
+        Self::new(Level::Warn, prefix)
+    }
+    /// Get a handle to the epoch, which the caller increments to change the tag of subsequent log statements
+    pub fn epoch_handle(&self) -> Arc<AtomicU64> {
+        Arc::clone(&self.epoch)
+    }
+}
+impl<'a, T: Display> Service for EpochLogDisplayService<'a, T> {
+    type Input = T;
+    type Output = T;
+    type Error = ();
+    fn process(&self, input: T) -> Result<Self::Output, Self::Error> {
+        self.config.log(format_args!(
+            "[epoch {}] {}",
+            self.epoch.load(Ordering::Relaxed),
+            input
+        ));
+        Ok(input)
+    }
+}
+
+/// A [`sod::Service`] that logs [`Display`] input at a configured log level to [`log::log`], returning the input as output, which is `'static` and cheap to clone.
+///
+/// The prefix is stored as an [`Arc<str>`] and clones share the same configured [`LogDisplayService`] through an [`Arc`], so cloning never duplicates the prefix.
+/// This service is useful for sharing a logging stage across threads, and is configured by converting a configured `LogDisplayService<'static, T>` with [`From`].
+pub struct LogDisplayServiceShared<T> {
+    service: Arc<LogDisplayService<'static, T>>,
+    prefix: Arc<str>,
+}
+impl<T> LogDisplayServiceShared<T> {
+    /// Log input at the given log level
+    /// # Arguments
+    /// * `level` - The log level
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    pub fn new<S: Into<Cow<'static, str>>>(level: Level, prefix: S) -> Self {
+        LogDisplayService::new(level, prefix).into()
+    }
+    /// Log input at the given log level, rejecting a prefix that contains ASCII control characters
+    /// # Arguments
+    /// * `level` - The log level
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    pub fn try_new<S: Into<Cow<'static, str>>>(
+        level: Level,
+        prefix: S,
+    ) -> Result<Self, PrefixError> {
+        LogDisplayService::try_new(level, prefix).map(Into::into)
+    }
+    /// Log as [`Level::Debug`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    pub fn debug<S: Into<Cow<'static, str>>>(prefix: S) -> Self {
+        Self::new(Level::Debug, prefix)
+    }
+    /// Log as [`Level::Error`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    pub fn error<S: Into<Cow<'static, str>>>(prefix: S) -> Self {
+        Self::new(Level::Error, prefix)
+    }
+    /// Log as [`Level::Info`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    pub fn info<S: Into<Cow<'static, str>>>(prefix: S) -> Self {
+        Self::new(Level::Info, prefix)
+    }
+    /// Log as [`Level::Trace`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    pub fn trace<S: Into<Cow<'static, str>>>(prefix: S) -> Self {
+        Self::new(Level::Trace, prefix)
+    }
+    /// Log as [`Level::Warn`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    pub fn warn<S: Into<Cow<'static, str>>>(prefix: S) -> Self {
+        Self::new(Level::Warn, prefix)
+    }
+    /// Get the configured log level
+    pub fn level(&self) -> Level {
+        self.service.level()
+    }
+    /// Get the configured prefix
+    pub fn prefix(&self) -> &str {
+        &self.prefix
+    }
+}
+impl<T> Clone for LogDisplayServiceShared<T> {
+    fn clone(&self) -> Self {
+        Self {
+            service: Arc::clone(&self.service),
+            prefix: Arc::clone(&self.prefix),
+        }
+    }
+}
+impl<T> From<LogDisplayService<'static, T>> for LogDisplayServiceShared<T> {
+    fn from(mut service: LogDisplayService<'static, T>) -> Self {
+        // the prefix is moved out of the service, so it is stored once
+        let prefix = std::mem::take(&mut service.config.prefix).into_owned();
+        Self {
+            service: Arc::new(service),
+            prefix: prefix.into(),
+        }
+    }
+}
+impl<T: Display> Service for LogDisplayServiceShared<T> {
+    type Input = T;
+    type Output = T;
+    type Error = ();
+    fn process(&self, input: T) -> Result<Self::Output, Self::Error> {
+        let sequence = self.service.count();
+        self.service.log_with(sequence, &self.prefix, &input);
+        Ok(input)
+    }
+}
+impl<T> Display for LogDisplayServiceShared<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.service
+            .config
+            .fmt_summary(f, "LogDisplayServiceShared", &self.prefix)
+    }
+}
+
+/// A [`sod::Service`] that logs the signed difference between numeric input and the previous input at a configured log level to [`log::log`], returning the input as output.
+///
+/// Each difference is logged in the form of `{prefix}+5` or `{prefix}-3`, and the first input is logged in the form of `{prefix}initial {input}`.
+/// The smaller value is always subtracted from the larger one, so a decrease of unsigned input does not underflow.
+/// This service is useful for monitoring counters, where the change is more interesting than the total.
+pub struct LogDeltaService<'a, T> {
+    config: LogConfig<'a>,
+    last: Mutex<Option<T>>,
+}
+impl<'a, T> LogDeltaService<'a, T> {
+    /// Log input at the given log level
+    /// # Arguments
+    /// * `level` - The log level
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    pub fn new<S: Into<Cow<'a, str>>>(level: Level, prefix: S) -> Self {
+        Self {
+            config: LogConfig::new(level, prefix.into()),
+            last: Mutex::new(None),
+        }
+    }
+    /// Log as [`Level::Debug`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    pub fn debug<S: Into<Cow<'a, str>>>(prefix: S) -> Self {
+        Self::new(Level::Debug, prefix)
+    }
+    /// Log as [`Level::Error`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    pub fn error<S: Into<Cow<'a, str>>>(prefix: S) -> Self {
+        Self::new(Level::Error, prefix)
+    }
+    /// Log as [`Level::Info`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    pub fn info<S: Into<Cow<'a, str>>>(prefix: S) -> Self {
+        Self::new(Level::Info, prefix)
+    }
+    /// Log as [`Level::Trace`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    pub fn trace<S: Into<Cow<'a, str>>>(prefix: S) -> Self {
+        Self::new(Level::Trace, prefix)
+    }
+    /// Log as [`Level::Warn`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    pub fn warn<S: Into<Cow<'a, str>>>(prefix: S) -> Self {
+        Self::new(Level::Warn, prefix)
+    }
+}
+impl<'a, T: Copy + Display + PartialOrd + Sub> Service for LogDeltaService<'a, T>
+where
+    T::Output: Display,
+{
+    type Input = T;
+    type Output = T;
+    type Error = ();
+    fn process(&self, input: T) -> Result<Self::Output, Self::Error> {
+        let mut last = self.last.lock().unwrap_or_else(PoisonError::into_inner);
+        match last.replace(input) {
+            Some(last) if input >= last => self.config.log(format_args!("+{}", input - last)),
+            Some(last) => self.config.log(format_args!("-{}", last - input)),
+            None => self.config.log(format_args!("initial {}", input)),
+        }
+        Ok(input)
+    }
+}
+
+/// A [`sod::Service`] that logs [`Display`] input at a configured log level to [`log::log`] and then blocks the current thread for a configured delay, returning the input as output.
+///
+/// **This service is a debugging tool and must not be used in production.** It deliberately slows a service chain down so its log statements can be read as they happen,
+/// and blocks the calling thread with [`std::thread::sleep`] for every input, including when the log level is disabled.
+/// This service requires the `debug-tools` feature.
+#[cfg(feature = "debug-tools")]
+pub struct LogPaceService<'a, T> {
+    config: LogConfig<'a>,
+    delay: Duration,
+    _phantom: PhantomData<fn(T)>,
+}
+#[cfg(feature = "debug-tools")]
+impl<'a, T> LogPaceService<'a, T> {
+    /// Log input at the given log level
+    /// # Arguments
+    /// * `level` - The log level
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `delay` - The duration to sleep after logging each input
+    pub fn new<S: Into<Cow<'a, str>>>(level: Level, prefix: S, delay: Duration) -> Self {
+        Self {
+            config: LogConfig::new(level, prefix.into()),
+            delay,
+            _phantom: PhantomData,
+        }
+    }
+    /// Log as [`Level::Debug`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `delay` - The duration to sleep after logging each input
+    pub fn debug<S: Into<Cow<'a, str>>>(prefix: S, delay: Duration) -> Self {
+        Self::new(Level::Debug, prefix, delay)
+    }
+    /// Log as [`Level::Error`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `delay` - The duration to sleep after logging each input
+    pub fn error<S: Into<Cow<'a, str>>>(prefix: S, delay: Duration) -> Self {
+        Self::new(Level::Error, prefix, delay)
+    }
+    /// Log as [`Level::Info`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `delay` - The duration to sleep after logging each input
+    pub fn info<S: Into<Cow<'a, str>>>(prefix: S, delay: Duration) -> Self {
+        Self::new(Level::Info, prefix, delay)
+    }
+    /// Log as [`Level::Trace`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `delay` - The duration to sleep after logging each input
+    pub fn trace<S: Into<Cow<'a, str>>>(prefix: S, delay: Duration) -> Self {
+        Self::new(Level::Trace, prefix, delay)
+    }
+    /// Log as [`Level::Warn`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `delay` - The duration to sleep after logging each input
+    pub fn warn<S: Into<Cow<'a, str>>>(prefix: S, delay: Duration) -> Self {
+        Self::new(Level::Warn, prefix, delay)
+    }
+}
+#[cfg(feature = "debug-tools")]
+impl<'a, T: Display> Service for LogPaceService<'a, T> {
+    type Input = T;
+    type Output = T;
+    type Error = ();
+    fn process(&self, input: T) -> Result<Self::Output, Self::Error> {
+        self.config.log(format_args!("{}", input));
+        std::thread::sleep(self.delay);
+        Ok(input)
+    }
+}
+
+/// A [`sod::Service`] that buffers [`Display`] input, logging it at a configured log level to [`log::log`] only when committed, returning the input as output.
+///
+/// Buffered input is logged by [`DeferredLogDisplayService::commit`] or discarded by [`DeferredLogDisplayService::rollback`].
+/// This service is useful for transactional pipelines, where the log statements of a transaction are only of interest if it commits.
+/// Input is formatted when it is processed, and is not buffered while the log level is disabled.
+pub struct DeferredLogDisplayService<'a, T> {
+    config: LogConfig<'a>,
+    buffer: Mutex<Vec<String>>,
+    _phantom: PhantomData<fn(T)>,
+}
+impl<'a, T> DeferredLogDisplayService<'a, T> {
+    /// Log input at the given log level
+    /// # Arguments
+    /// * `level` - The log level
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    pub fn new<S: Into<Cow<'a, str>>>(level: Level, prefix: S) -> Self {
+        Self {
+            config: LogConfig::new(level, prefix.into()),
+            buffer: Mutex::new(Vec::new()),
+            _phantom: PhantomData,
+        }
+    }
+    /// Log as [`Level::Debug`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    pub fn debug<S: Into<Cow<'a, str>>>(prefix: S) -> Self {
+        Self::new(Level::Debug, prefix)
+    }
+    /// Log as [`Level::Error`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    pub fn error<S: Into<Cow<'a, str>>>(prefix: S) -> Self {
+        Self::new(Level::Error, prefix)
+    }
+    /// Log as [`Level::Info`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    pub fn info<S: Into<Cow<'a, str>>>(prefix: S) -> Self {
+        Self::new(Level::Info, prefix)
+    }
+    /// Log as [`Level::Trace`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    pub fn trace<S: Into<Cow<'a, str>>>(prefix: S) -> Self {
+        Self::new(Level::Trace, prefix)
+    }
+    /// Log as [`Level::Warn`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    pub fn warn<S: Into<Cow<'a, str>>>(prefix: S) -> Self {
+        Self::new(Level::Warn, prefix)
+    }
+    /// Log all buffered input in the order it was processed, emptying the buffer
+    pub fn commit(&self) {
+        let buffer =
+            std::mem::take(&mut *self.buffer.lock().unwrap_or_else(PoisonError::into_inner));
+        for message in buffer {
+            self.config.log(format_args!("{}", message));
+        }
+    }
+    /// Discard all buffered input without logging it
+    pub fn rollback(&self) {
+        self.buffer
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clear();
+    }
+}
+impl<'a, T: Display> Service for DeferredLogDisplayService<'a, T> {
+    type Input = T;
+    type Output = T;
+    type Error = ();
+    fn process(&self, input: T) -> Result<Self::Output, Self::Error> {
+        if self.config.is_enabled() {
+            self.buffer
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .push(input.to_string());
+        }
+        Ok(input)
+    }
+}
+
+/// A [`sod::Service`] that logs the first configured number of [`Display`] inputs at a configured log level to [`log::log`], returning the input as output.
+///
+/// This service is useful for logging a warmup window of a service chain, after which it goes silent. A limit of `0` logs nothing.
+pub struct LogFirstNService<'a, T> {
+    config: LogConfig<'a>,
+    limit: usize,
+    logged: AtomicUsize,
+    _phantom: PhantomData<fn(T)>,
+}
+impl<'a, T> LogFirstNService<'a, T> {
+    /// Log input at the given log level
+    /// # Arguments
+    /// * `level` - The log level
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `limit` - The number of inputs to log
+    pub fn new<S: Into<Cow<'a, str>>>(level: Level, prefix: S, limit: usize) -> Self {
+        Self {
+            config: LogConfig::new(level, prefix.into()),
+            limit,
+            logged: AtomicUsize::new(0),
+            _phantom: PhantomData,
+        }
+    }
+    /// Log as [`Level::Debug`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `limit` - The number of inputs to log
+    pub fn debug<S: Into<Cow<'a, str>>>(prefix: S, limit: usize) -> Self {
+        Self::new(Level::Debug, prefix, limit)
+    }
+    /// Log as [`Level::Error`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `limit` - The number of inputs to log
+    pub fn error<S: Into<Cow<'a, str>>>(prefix: S, limit: usize) -> Self {
+        Self::new(Level::Error, prefix, limit)
+    }
+    /// Log as [`Level::Info`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `limit` - The number of inputs to log
+    pub fn info<S: Into<Cow<'a, str>>>(prefix: S, limit: usize) -> Self {
+        Self::new(Level::Info, prefix, limit)
+    }
+    /// Log as [`Level::Trace`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `limit` - The number of inputs to log
+    pub fn trace<S: Into<Cow<'a, str>>>(prefix: S, limit: usize) -> Self {
+        Self::new(Level::Trace, prefix, limit)
+    }
+    /// Log as [`Level::Warn`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `limit` - The number of inputs to log
+    pub fn warn<S: Into<Cow<'a, str>>>(prefix: S, limit: usize) -> Self {
+        Self::new(Level::Warn, prefix, limit)
+    }
+}
+impl<'a, T: Display> Service for LogFirstNService<'a, T> {
+    type Input = T;
+    type Output = T;
+    type Error = ();
+    fn process(&self, input: T) -> Result<Self::Output, Self::Error> {
+        let within_limit = self
+            .logged
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |logged| {
+                (logged < self.limit).then_some(logged + 1)
+            })
+            .is_ok();
+        if within_limit {
+            self.config.log(format_args!("{}", input));
+        }
+        Ok(input)
+    }
+}
+
+/// A [`sod::Service`] that logs the slot of each input within a repeating cycle at a configured log level to [`log::log`], returning the input as output.
+///
+/// The slot is logged in the form of `{prefix}slot={slot}`, where the slot is the number of previously processed inputs modulo the configured modulus,
+/// cycling through `0, 1, .., modulus - 1`. This service is useful for round-robin or sharded pipelines.
+pub struct CyclicIndexLogService<'a, T> {
+    config: LogConfig<'a>,
+    modulus: NonZeroU64,
+    count: AtomicU64,
+    _phantom: PhantomData<fn(T)>,
+}
+impl<'a, T> CyclicIndexLogService<'a, T> {
+    /// Log input at the given log level
+    /// # Arguments
+    /// * `level` - The log level
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `modulus` - The number of slots in the cycle, which is non-zero so every input has a slot
+    pub fn new<S: Into<Cow<'a, str>>>(level: Level, prefix: S, modulus: NonZeroU64) -> Self {
+        Self {
+            config: LogConfig::new(level, prefix.into()),
+            modulus,
+            count: AtomicU64::new(0),
+            _phantom: PhantomData,
+        }
+    }
+    /// Log as [`Level::Debug`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `modulus` - The number of slots in the cycle, which is non-zero so every input has a slot
+    pub fn debug<S: Into<Cow<'a, str>>>(prefix: S, modulus: NonZeroU64) -> Self {
+        Self::new(Level::Debug, prefix, modulus)
+    }
+    /// Log as [`Level::Error`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `modulus` - The number of slots in the cycle, which is non-zero so every input has a slot
+    pub fn error<S: Into<Cow<'a, str>>>(prefix: S, modulus: NonZeroU64) -> Self {
+        Self::new(Level::Error, prefix, modulus)
+    }
+    /// Log as [`Level::Info`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `modulus` - The number of slots in the cycle, which is non-zero so every input has a slot
+    pub fn info<S: Into<Cow<'a, str>>>(prefix: S, modulus: NonZeroU64) -> Self {
+        Self::new(Level::Info, prefix, modulus)
+    }
+    /// Log as [`Level::Trace`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `modulus` - The number of slots in the cycle, which is non-zero so every input has a slot
+    pub fn trace<S: Into<Cow<'a, str>>>(prefix: S, modulus: NonZeroU64) -> Self {
+        Self::new(Level::Trace, prefix, modulus)
+    }
+    /// Log as [`Level::Warn`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `modulus` - The number of slots in the cycle, which is non-zero so every input has a slot
+    pub fn warn<S: Into<Cow<'a, str>>>(prefix: S, modulus: NonZeroU64) -> Self {
+        Self::new(Level::Warn, prefix, modulus)
+    }
+}
+impl<'a, T> Service for CyclicIndexLogService<'a, T> {
+    type Input = T;
+    type Output = T;
+    type Error = ();
+    fn process(&self, input: T) -> Result<Self::Output, Self::Error> {
+        let count = self.count.fetch_add(1, Ordering::Relaxed);
+        self.config
+            .log(format_args!("slot={}", count % self.modulus));
+        Ok(input)
+    }
+}
+
+/// A [`sod::Service`] that logs [`Display`] input at a configured log level to [`log::log`], returning the input converted with [`From`] as output.
+///
+/// This service merges logging with a common conversion step, such as `LogIntoService::<u8, u32>::info("byte: ")`.
+pub struct LogIntoService<'a, T, U> {
+    config: LogConfig<'a>,
+    _phantom: PhantomData<fn(T) -> U>,
+}
+impl<'a, T, U> LogIntoService<'a, T, U> {
+    /// Log input at the given log level
+    /// # Arguments
+    /// * `level` - The log level
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    pub fn new<S: Into<Cow<'a, str>>>(level: Level, prefix: S) -> Self {
+        Self {
+            config: LogConfig::new(level, prefix.into()),
+            _phantom: PhantomData,
+        }
+    }
+    /// Log as [`Level::Debug`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    pub fn debug<S: Into<Cow<'a, str>>>(prefix: S) -> Self {
+        Self::new(Level::Debug, prefix)
+    }
+    /// Log as [`Level::Error`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    pub fn error<S: Into<Cow<'a, str>>>(prefix: S) -> Self {
+        Self::new(Level::Error, prefix)
+    }
+    /// Log as [`Level::Info`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    pub fn info<S: Into<Cow<'a, str>>>(prefix: S) -> Self {
+        Self::new(Level::Info, prefix)
+    }
+    /// Log as [`Level::Trace`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    pub fn trace<S: Into<Cow<'a, str>>>(prefix: S) -> Self {
+        Self::new(Level::Trace, prefix)
+    }
+    /// Log as [`Level::Warn`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    pub fn warn<S: Into<Cow<'a, str>>>(prefix: S) -> Self {
+        Self::new(Level::Warn, prefix)
+    }
+}
+impl<'a, T: Display, U: From<T>> Service for LogIntoService<'a, T, U> {
+    type Input = T;
+    type Output = U;
+    type Error = ();
+    fn process(&self, input: T) -> Result<Self::Output, Self::Error> {
+        self.config.log(format_args!("{}", input));
+        Ok(U::from(input))
+    }
+}
+
+/// A [`sod::Service`] that transforms [`Display`] input with a function, logging both the input and the output at a configured log level to [`log::log`], returning the output.
+///
+/// Unlike most services, this service does not pass its input through: the returned output is the result of the function.
+/// The input and output are logged as separate log statements, each with its own prefix.
+pub struct LogMapService<'a, T, U, F> {
+    input: LogConfig<'a>,
+    output: LogConfig<'a>,
+    map: F,
+    _phantom: PhantomData<fn(T) -> U>,
+}
+impl<'a, T, U, F: Fn(T) -> U> LogMapService<'a, T, U, F> {
+    /// Log input and output at the given log level
+    /// # Arguments
+    /// * `level` - The log level
+    /// * `input_prefix` - A prefix to prepend to the beginning of the log statement of the input
+    /// * `output_prefix` - A prefix to prepend to the beginning of the log statement of the output
+    /// * `map` - The function that transforms input to output
+    pub fn new<I: Into<Cow<'a, str>>, O: Into<Cow<'a, str>>>(
+        level: Level,
+        input_prefix: I,
+        output_prefix: O,
+        map: F,
+    ) -> Self {
+        Self {
+            input: LogConfig::new(level, input_prefix.into()),
+            output: LogConfig::new(level, output_prefix.into()),
+            map,
+            _phantom: PhantomData,
+        }
+    }
+    /// Log as [`Level::Debug`]
+    /// # Arguments
+    /// * `input_prefix` - A prefix to prepend to the beginning of the log statement of the input
+    /// * `output_prefix` - A prefix to prepend to the beginning of the log statement of the output
+    /// * `map` - The function that transforms input to output
+    pub fn debug<I: Into<Cow<'a, str>>, O: Into<Cow<'a, str>>>(
+        input_prefix: I,
+        output_prefix: O,
+        map: F,
+    ) -> Self {
+        Self::new(Level::Debug, input_prefix, output_prefix, map)
+    }
+    /// Log as [`Level::Error`]
+    /// # Arguments
+    /// * `input_prefix` - A prefix to prepend to the beginning of the log statement of the input
+    /// * `output_prefix` - A prefix to prepend to the beginning of the log statement of the output
+    /// * `map` - The function that transforms input to output
+    pub fn error<I: Into<Cow<'a, str>>, O: Into<Cow<'a, str>>>(
+        input_prefix: I,
+        output_prefix: O,
+        map: F,
+    ) -> Self {
+        Self::new(Level::Error, input_prefix, output_prefix, map)
+    }
+    /// Log as [`Level::Info`]
+    /// # Arguments
+    /// * `input_prefix` - A prefix to prepend to the beginning of the log statement of the input
+    /// * `output_prefix` - A prefix to prepend to the beginning of the log statement of the output
+    /// * `map` - The function that transforms input to output
+    pub fn info<I: Into<Cow<'a, str>>, O: Into<Cow<'a, str>>>(
+        input_prefix: I,
+        output_prefix: O,
+        map: F,
+    ) -> Self {
+        Self::new(Level::Info, input_prefix, output_prefix, map)
+    }
+    /// Log as [`Level::Trace`]
+    /// # Arguments
+    /// * `input_prefix` - A prefix to prepend to the beginning of the log statement of the input
+    /// * `output_prefix` - A prefix to prepend to the beginning of the log statement of the output
+    /// * `map` - The function that transforms input to output
+    pub fn trace<I: Into<Cow<'a, str>>, O: Into<Cow<'a, str>>>(
+        input_prefix: I,
+        output_prefix: O,
+        map: F,
+    ) -> Self {
+        Self::new(Level::Trace, input_prefix, output_prefix, map)
+    }
+    /// Log as [`Level::Warn`]
+    /// # Arguments
+    /// * `input_prefix` - A prefix to prepend to the beginning of the log statement of the input
+    /// * `output_prefix` - A prefix to prepend to the beginning of the log statement of the output
+    /// * `map` - The function that transforms input to output
+    pub fn warn<I: Into<Cow<'a, str>>, O: Into<Cow<'a, str>>>(
+        input_prefix: I,
+        output_prefix: O,
+        map: F,
+    ) -> Self {
+        Self::new(Level::Warn, input_prefix, output_prefix, map)
+    }
+}
+impl<'a, T: Display, U: Display, F: Fn(T) -> U> Service for LogMapService<'a, T, U, F> {
+    type Input = T;
+    type Output = U;
+    type Error = ();
+    fn process(&self, input: T) -> Result<Self::Output, Self::Error> {
+        self.input.log(format_args!("{}", input));
+        let output = (self.map)(input);
+        self.output.log(format_args!("{}", output));
+        Ok(output)
+    }
+}
+
+/// A wrapper marking a value that a [`SuppressibleLogService`] should pass through without logging, which converts into [`Loggable::Skip`].
+///
+/// `NoLog` deliberately implements neither [`Display`] nor [`Debug`], so a wrapped value can never be logged by accident.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct NoLog<T>(pub T);
+
+/// The input of a [`SuppressibleLogService`], marking whether a value is logged.
+///
+/// Like [`NoLog`], this implements neither [`Display`] nor [`Debug`], so a skipped value can never be logged by accident.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Loggable<T> {
+    /// A value that is passed to the logging service
+    Log(T),
+    /// A value that is passed through without logging
+    Skip(T),
+}
+impl<T> From<NoLog<T>> for Loggable<T> {
+    fn from(NoLog(value): NoLog<T>) -> Self {
+        Loggable::Skip(value)
+    }
+}
+
+/// A [`sod::Service`] that wraps a logging service, passing [`Loggable::Log`] input to it and unwrapping [`Loggable::Skip`] input without logging, returning the unwrapped input as output.
+///
+/// This service allows upstream stages to suppress logging of individual values without removing the logging stage.
+/// The input is a [`Loggable`] rather than a bare `NoLog<T>` because adding a second [`Service`] impl to the logging services
+/// themselves would break type inference for every existing caller. A `NoLog<T>` converts into a [`Loggable::Skip`] with [`Into`].
+/// ```
+/// use sod::Service;
+/// use sod_log::{LogDisplayService, Loggable, NoLog, SuppressibleLogService};
+///
+/// let logging_service = SuppressibleLogService::new(LogDisplayService::info("my event: "));
+/// assert_eq!(logging_service.process(Loggable::Log("logged")), Ok("logged"));
+/// assert_eq!(logging_service.process(NoLog("not logged").into()), Ok("not logged"));
+/// ```
+pub struct SuppressibleLogService<S> {
+    service: S,
+}
+impl<S> SuppressibleLogService<S> {
+    /// Suppress logging of `NoLog` input to the given service
+    /// # Arguments
+    /// * `service` - The underlying logging service, which must return its input as output
+    pub fn new(service: S) -> Self {
+        Self { service }
+    }
+}
+impl<S: Service<Output = <S as Service>::Input>> Service for SuppressibleLogService<S> {
+    type Input = Loggable<S::Input>;
+    type Output = S::Input;
+    type Error = S::Error;
+    fn process(&self, input: Self::Input) -> Result<Self::Output, Self::Error> {
+        match input {
+            Loggable::Log(input) => self.service.process(input),
+            Loggable::Skip(input) => Ok(input),
+        }
+    }
+}
+
+/// A [`sod::Service`] that logs the [`Display`] error of [`Result`] input at a configured log level to [`log::log`], additionally logging to an alert target
+/// at [`Level::Error`] while the error rate over a sliding window of recent inputs exceeds a threshold, returning the input as output.
+///
+/// The error rate is only measured once the window is full, so a single early error does not raise an alert.
+/// Each alert log statement is in the form of `{prefix}error rate {rate}%: {error}`.
+/// The constructors panic if the threshold is not between `0.0` and `1.0`.
+/// ```
+/// use sod::Service;
+/// use sod_log::AdaptiveAlertLogResultService;
+/// use std::num::NonZeroUsize;
+///
+/// let window = NonZeroUsize::new(10).unwrap();
+/// let logging_service = AdaptiveAlertLogResultService::<u32, &str>::warn("request failed: ", "alert", window, 0.5);
+/// assert_eq!(logging_service.process(Err("timeout")), Ok(Err("timeout")));
+/// ```
+pub struct AdaptiveAlertLogResultService<'a, T, E> {
+    config: LogConfig<'a>,
+    alert: LogConfig<'a>,
+    window: NonZeroUsize,
+    threshold: f64,
+    recent: Mutex<VecDeque<bool>>,
+    _phantom: PhantomData<fn(T, E)>,
+}
+impl<'a, T, E> AdaptiveAlertLogResultService<'a, T, E> {
+    /// Log errors at the given log level, alerting when the error rate exceeds the threshold
+    /// # Arguments
+    /// * `level` - The log level
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `alert_target` - The log target to additionally log to at [`Level::Error`] while the error rate exceeds the threshold
+    /// * `window` - The number of most recent inputs over which the error rate is measured
+    /// * `threshold` - The error rate between `0.0` and `1.0` that must be exceeded to log to the alert target
+    /// # Panics
+    /// If `threshold` is not between `0.0` and `1.0`
+    pub fn new<S: Into<Cow<'a, str>>, A: Into<Cow<'a, str>>>(
+        level: Level,
+        prefix: S,
+        alert_target: A,
+        window: NonZeroUsize,
+        threshold: f64,
+    ) -> Self {
+        assert!(
+            (0.0..=1.0).contains(&threshold),
+            "threshold must be between 0.0 and 1.0"
+        );
+        let prefix = prefix.into();
+        let mut alert = LogConfig::new(Level::Error, prefix.clone());
+        alert.target = Some(alert_target.into());
+        Self {
+            config: LogConfig::new(level, prefix),
+            alert,
+            window,
+            threshold,
+            recent: Mutex::new(VecDeque::with_capacity(window.get())),
+            _phantom: PhantomData,
+        }
+    }
+    /// Log as [`Level::Debug`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `alert_target` - The log target to additionally log to at [`Level::Error`] while the error rate exceeds the threshold
+    /// * `window` - The number of most recent inputs over which the error rate is measured
+    /// * `threshold` - The error rate between `0.0` and `1.0` that must be exceeded to log to the alert target
+    pub fn debug<S: Into<Cow<'a, str>>, A: Into<Cow<'a, str>>>(
+        prefix: S,
+        alert_target: A,
+        window: NonZeroUsize,
+        threshold: f64,
+    ) -> Self {
+        Self::new(Level::Debug, prefix, alert_target, window, threshold)
+    }
+    /// Log as [`Level::Error`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `alert_target` - The log target to additionally log to at [`Level::Error`] while the error rate exceeds the threshold
+    /// * `window` - The number of most recent inputs over which the error rate is measured
+    /// * `threshold` - The error rate between `0.0` and `1.0` that must be exceeded to log to the alert target
+    pub fn error<S: Into<Cow<'a, str>>, A: Into<Cow<'a, str>>>(
+        prefix: S,
+        alert_target: A,
+        window: NonZeroUsize,
+        threshold: f64,
+    ) -> Self {
+        Self::new(Level::Error, prefix, alert_target, window, threshold)
+    }
+    /// Log as [`Level::Info`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `alert_target` - The log target to additionally log to at [`Level::Error`] while the error rate exceeds the threshold
+    /// * `window` - The number of most recent inputs over which the error rate is measured
+    /// * `threshold` - The error rate between `0.0` and `1.0` that must be exceeded to log to the alert target
+    pub fn info<S: Into<Cow<'a, str>>, A: Into<Cow<'a, str>>>(
+        prefix: S,
+        alert_target: A,
+        window: NonZeroUsize,
+        threshold: f64,
+    ) -> Self {
+        Self::new(Level::Info, prefix, alert_target, window, threshold)
+    }
+    /// Log as [`Level::Trace`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `alert_target` - The log target to additionally log to at [`Level::Error`] while the error rate exceeds the threshold
+    /// * `window` - The number of most recent inputs over which the error rate is measured
+    /// * `threshold` - The error rate between `0.0` and `1.0` that must be exceeded to log to the alert target
+    pub fn trace<S: Into<Cow<'a, str>>, A: Into<Cow<'a, str>>>(
+        prefix: S,
+        alert_target: A,
+        window: NonZeroUsize,
+        threshold: f64,
+    ) -> Self {
+        Self::new(Level::Trace, prefix, alert_target, window, threshold)
+    }
+    /// Log as [`Level::Warn`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    /// * `alert_target` - The log target to additionally log to at [`Level::Error`] while the error rate exceeds the threshold
+    /// * `window` - The number of most recent inputs over which the error rate is measured
+    /// * `threshold` - The error rate between `0.0` and `1.0` that must be exceeded to log to the alert target
+    pub fn warn<S: Into<Cow<'a, str>>, A: Into<Cow<'a, str>>>(
+        prefix: S,
+        alert_target: A,
+        window: NonZeroUsize,
+        threshold: f64,
+    ) -> Self {
+        Self::new(Level::Warn, prefix, alert_target, window, threshold)
+    }
+}
+impl<'a, T, E: Display> Service for AdaptiveAlertLogResultService<'a, T, E> {
+    type Input = Result<T, E>;
+    type Output = Result<T, E>;
+    type Error = ();
+    fn process(&self, input: Result<T, E>) -> Result<Self::Output, Self::Error> {
+        // the error rate is measured under the lock, which is released before logging
+        let rate = {
+            let mut recent = self.recent.lock().unwrap_or_else(PoisonError::into_inner);
+            if recent.len() >= self.window.get() {
+                recent.pop_front();
+            }
+            recent.push_back(input.is_err());
+            (recent.len() >= self.window.get()).then(|| {
+                recent.iter().filter(|&&failed| failed).count() as f64 / recent.len() as f64
+            })
+        };
+        if let Err(err) = &input {
+            self.config.log(format_args!("{}", err));
+            if let Some(rate) = rate.filter(|&rate| rate > self.threshold) {
+                self.alert
+                    .log(format_args!("error rate {:.0}%: {}", rate * 100.0, err));
+            }
+        }
+        Ok(input)
+    }
+}
+
+/// A [`sod::Service`] that logs [`Display`] input as a logfmt line at a configured log level to [`log::log`], returning the input as output.
+///
+/// Each log statement is in the form of `level=info prefix=event value=hello`. Values that are empty or contain spaces, `=`, quotes,
+/// backslashes, or control characters are quoted, with embedded quotes and backslashes escaped, such as `value="say \"hi\""`.
+/// The default prefix set by [`set_default_prefix`] is prepended to the `prefix` value rather than to the line, so the line remains valid logfmt.
+/// ```
+/// use sod::Service;
+/// use sod_log::LogLogfmtService;
+///
+/// let logging_service = LogLogfmtService::info("event");
+/// logging_service.process("hello world").unwrap();
+/// ```
+pub struct LogLogfmtService<'a, T> {
+    config: LogConfig<'a>,
+    _phantom: PhantomData<fn(T)>,
+}
+impl<'a, T> LogLogfmtService<'a, T> {
+    /// Log input at the given log level
+    /// # Arguments
+    /// * `level` - The log level
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    pub fn new<S: Into<Cow<'a, str>>>(level: Level, prefix: S) -> Self {
+        let mut config = LogConfig::new(level, prefix.into());
+        // the default prefix is part of the prefix value, since any text before the first key is invalid logfmt
+        let default_prefix = std::mem::take(&mut config.default_prefix);
+        if !default_prefix.is_empty() {
+            config.prefix = Cow::Owned(default_prefix + &config.prefix);
+        }
+        Self {
+            config,
+            _phantom: PhantomData,
+        }
+    }
+    /// Log as [`Level::Debug`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    pub fn debug<S: Into<Cow<'a, str>>>(prefix: S) -> Self {
+        Self::new(Level::Debug, prefix)
+    }
+    /// Log as [`Level::Error`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    pub fn error<S: Into<Cow<'a, str>>>(prefix: S) -> Self {
+        Self::new(Level::Error, prefix)
+    }
+    /// Log as [`Level::Info`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    pub fn info<S: Into<Cow<'a, str>>>(prefix: S) -> Self {
+        Self::new(Level::Info, prefix)
+    }
+    /// Log as [`Level::Trace`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    pub fn trace<S: Into<Cow<'a, str>>>(prefix: S) -> Self {
+        Self::new(Level::Trace, prefix)
+    }
+    /// Log as [`Level::Warn`]
+    /// # Arguments
+    /// * `prefix` - A prefix to prepend to the beginning of the log statement
+    pub fn warn<S: Into<Cow<'a, str>>>(prefix: S) -> Self {
+        Self::new(Level::Warn, prefix)
+    }
+}
+impl<'a, T: Display> Service for LogLogfmtService<'a, T> {
+    type Input = T;
+    type Output = T;
+    type Error = ();
+    fn process(&self, input: T) -> Result<Self::Output, Self::Error> {
+        if self.config.will_emit() {
+            let level = self.config.level;
+            self.config.log_prefixed(
+                "",
+                format_args!(
+                    "level={} prefix={} value={}",
+                    level.as_str().to_ascii_lowercase(),
+                    LogfmtValue(self.config.prefix(level)),
+                    LogfmtValue(&input.to_string())
+                ),
+            );
+        }
+        Ok(input)
+    }
+}
+
+/// A logfmt value, quoted and escaped only when required
+struct LogfmtValue<'v>(&'v str);
+impl<'v> Display for LogfmtValue<'v> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let quoted = self.0.is_empty()
+            || self
+                .0
+                .chars()
+                .any(|c| c == ' ' || c == '=' || c == '"' || c == '\\' || c.is_control());
+        if !quoted {
+            return f.write_str(self.0);
+        }
+        f.write_str("\"")?;
+        for c in self.0.chars() {
+            match c {
+                '"' => f.write_str("\\\"")?,
+                '\\' => f.write_str("\\\\")?,
+                '\n' => f.write_str("\\n")?,
+                '\r' => f.write_str("\\r")?,
+                '\t' => f.write_str("\\t")?,
+                c => fmt::Write::write_char(f, c)?,
+            }
+        }
+        f.write_str("\"")
+    }
+}
+
+/// Common configuration shared by the log services
+struct LogConfig<'a> {
+    level: Level,
+    off: bool,
+    prefix: Cow<'a, str>,
+    level_prefixes: HashMap<Level, Cow<'a, str>>,
+    remap: Option<Box<dyn Fn(Level) -> Level + Send + Sync + 'a>>,
+    target: Option<Cow<'a, str>>,
+    fallback_stderr: bool,
+    max_len: Option<usize>,
+    trim: bool,
+    width: Option<usize>,
+    alignment: fmt::Alignment,
+    case: LogCase,
+    newlines: Newlines,
+    level_tag: bool,
+    thread_info: bool,
+    syslog_severity: bool,
+    id: u64,
+    show_id: bool,
+    context: Vec<(&'static str, String)>,
+    location: Option<Location>,
+    flush_every: u64,
+    #[cfg(not(feature = "tracing"))]
+    emitted: AtomicU64,
+    drop_message: Option<Cow<'a, str>>,
+    default_prefix: String,
+    captured: Option<Mutex<Option<String>>>,
+}
+impl<'a> LogConfig<'a> {
+    fn new(level: Level, prefix: Cow<'a, str>) -> Self {
+        Self {
+            level,
+            off: false,
+            prefix,
+            level_prefixes: HashMap::new(),
+            remap: None,
+            target: None,
+            fallback_stderr: false,
+            max_len: None,
+            trim: false,
+            width: None,
+            alignment: fmt::Alignment::Left,
+            case: LogCase::AsIs,
+            newlines: Newlines::Keep,
+            level_tag: false,
+            thread_info: false,
+            syslog_severity: false,
+            id: NEXT_ID.fetch_add(1, Ordering::Relaxed),
+            show_id: false,
+            context: Vec::new(),
+            location: None,
+            flush_every: 0,
+            #[cfg(not(feature = "tracing"))]
+            emitted: AtomicU64::new(0),
+            drop_message: None,
+            default_prefix: DEFAULT_PREFIX
+                .read()
+                .unwrap_or_else(PoisonError::into_inner)
+                .clone(),
+            captured: None,
+        }
+    }
+    /// Log the prefix for the level followed by the given formatted value
+    fn log(&self, value: Arguments) {
+        self.log_prefixed(self.prefix(self.level), value)
+    }
+    /// Log the given prefix followed by the given formatted value
+    fn log_prefixed(&self, prefix: &str, value: Arguments) {
+        // checked before formatting, so nothing is formatted or allocated for a disabled level
+        if !self.will_emit() {
+            return;
+        }
+        if !self.is_transformed() {
+            return self.emit(prefix, value);
+        }
+        let logged = FORMAT_BUFFER.try_with(|buffer| match buffer.try_borrow_mut() {
+            Ok(mut buffer) => {
+                buffer.clear();
+                self.log_transformed(prefix, value, &mut buffer);
+                if buffer.capacity() > MAX_RETAINED_BUFFER {
+                    *buffer = String::new();
+                }
+            }
+            // the buffer is already in use when formatting the input logs on the same thread
+            Err(_) => self.log_transformed(prefix, value, &mut String::new()),
+        });
+        // the buffer has been destroyed when a service is dropped during thread-local teardown
+        if logged.is_err() {
+            self.log_transformed(prefix, value, &mut String::new());
+        }
+    }
+    /// Format the given value into the given empty buffer, apply the transformations, and log it
+    fn log_transformed(&self, prefix: &str, value: Arguments, buffer: &mut String) {
+        match self.max_len {
+            Some(max_len) => {
+                let mut writer = TruncatingWriter::new(buffer, max_len);
+                // an error means formatting was aborted upon reaching max_len
+                let _ = fmt::write(&mut writer, value);
+                writer.finish();
+            }
+            None => {
+                let _ = fmt::write(buffer, value);
+            }
+        }
+        if self.trim {
+            buffer.truncate(buffer.trim_end().len());
+        }
+        let cased = match self.case {
+            LogCase::AsIs => None,
+            LogCase::Upper => Some(buffer.to_uppercase()),
+            LogCase::Lower => Some(buffer.to_lowercase()),
+        };
+        if let Some(cased) = cased {
+            buffer.clear();
+            buffer.push_str(&cased);
+        }
+        if let Some(width) = self.width {
+            pad(buffer, width, self.alignment);
+        }
+        let value = buffer.as_str();
+        match self.newlines {
+            Newlines::Keep => self.emit(prefix, format_args!("{}", value)),
+            Newlines::Split if value.is_empty() => self.emit(prefix, format_args!("")),
+            Newlines::Split => {
+                for line in value.lines() {
+                    self.emit(prefix, format_args!("{}", line));
+                }
+            }
+            Newlines::Strip => self.emit(prefix, format_args!("{}", Joined(value.lines()))),
+            Newlines::Collapse => {
+                self.emit(prefix, format_args!("{}", Joined(value.split_whitespace())))
+            }
+        }
+    }
+    /// Log the prefix followed by the given formatted value with the given key-values attached to the record.
+    ///
+    /// The key-values are formatted after the value instead when they cannot be attached, because the `tracing` backend is used or the statement falls back to stderr.
+    #[cfg(feature = "kv")]
+    fn emit_key_values(&self, value: Arguments, key_values: &dyn log::kv::Source) {
+        if self.off {
+            return;
+        }
+        let prefix = self.prefix(self.level);
+        #[cfg(not(feature = "tracing"))]
+        if !self.falls_back() {
+            let level = self.remapped(self.level);
+            self.capture(
+                level,
+                prefix,
+                format_args!("{}{}", value, KeyValues(key_values)),
+            );
+            self.dispatch_key_values(level, prefix, value, Some(key_values));
+            self.flush_if_due();
+            return;
+        }
+        self.emit(prefix, format_args!("{}{}", value, KeyValues(key_values)))
+    }
+    /// Build a record with the given message, attributed to the configured source location or to this crate
+    #[cfg(not(feature = "tracing"))]
+    fn record<'r>(&'r self, level: Level, args: Arguments<'r>) -> log::RecordBuilder<'r> {
+        let location = self.location.unwrap_or(Location {
+            module_path: module_path!(),
+            file: file!(),
+            line: line!(),
+        });
+        let mut builder = log::Record::builder();
+        builder
+            .level(level)
+            .target(self.target())
+            .args(args)
+            .module_path_static(Some(location.module_path))
+            .file_static(Some(location.file))
+            .line(Some(location.line));
+        builder
+    }
+    /// Format a single-line summary of the configuration in the form of `{name}(level={level}, prefix={prefix:?})`
+    fn fmt_summary(&self, f: &mut fmt::Formatter<'_>, name: &str, prefix: &str) -> fmt::Result {
+        let level = if self.off {
+            LevelFilter::Off
+        } else {
+            self.level.to_level_filter()
+        };
+        write!(f, "{}(level={}, prefix={:?})", name, level, prefix)
+    }
+    /// Check if any option requires the formatted value to be transformed before it is logged
+    fn is_transformed(&self) -> bool {
+        self.max_len.is_some()
+            || self.trim
+            || self.width.is_some()
+            || self.case != LogCase::AsIs
+            || self.newlines != Newlines::Keep
+    }
+    /// Log a warning about the service itself, such as a disconnected channel, with the configured target and prefix unless the service is filtered off
+    fn warn(&self, value: Arguments) {
+        self.emit_at(Level::Warn, self.prefix(self.level), value);
+    }
+    fn emit(&self, prefix: &str, value: Arguments) {
+        self.emit_at(self.level, prefix, value)
+    }
+    fn emit_at(&self, level: Level, prefix: &str, value: Arguments) {
+        if !self.will_emit_at(level) {
+            return;
+        }
+        let level = self.remapped(level);
+        self.capture(level, prefix, value);
+        if self.falls_back() {
+            eprintln!("{}{}{}", Tags(self, level, true), prefix, value);
+        } else {
+            self.dispatch(level, prefix, value);
+            self.flush_if_due();
+        }
+    }
+    /// Retain the log statement as the last message when enabled with `with_capture`
+    fn capture(&self, level: Level, prefix: &str, value: Arguments) {
+        if let Some(captured) = &self.captured {
+            *captured.lock().unwrap_or_else(PoisonError::into_inner) =
+                Some(self.line(level, prefix, value));
+        }
+    }
+    /// Check if log statements fall back to stderr, because `with_fallback_stderr` is set and no logger is installed
+    #[cfg(not(feature = "tracing"))]
+    fn falls_back(&self) -> bool {
+        self.fallback_stderr && log::max_level() == LevelFilter::Off
+    }
+    /// Check if log statements fall back to stderr, because `with_fallback_stderr` is set and no subscriber is set
+    #[cfg(feature = "tracing")]
+    fn falls_back(&self) -> bool {
+        self.fallback_stderr
+            && tracing::dispatcher::get_default(|dispatch| {
+                dispatch.is::<tracing::subscriber::NoSubscriber>()
+            })
+    }
+    /// Format the log statement as it is emitted at the given level, including the tags
+    fn line(&self, level: Level, prefix: &str, value: Arguments) -> String {
+        format!("{}{}{}", Tags(self, level, true), prefix, value)
+    }
+    #[cfg(not(feature = "tracing"))]
+    fn dispatch(&self, level: Level, prefix: &str, value: Arguments) {
+        #[cfg(feature = "kv")]
+        if !self.context.is_empty() {
+            return self.dispatch_key_values(level, prefix, value, None);
+        }
+        if self.location.is_some() {
+            // like `log::log!`, the level is checked against the `max_level_*` features of `log` as well as the runtime maximum level
+            if level <= log::STATIC_MAX_LEVEL && level <= log::max_level() {
+                log::logger().log(
+                    &self
+                        .record(
+                            level,
+                            format_args!("{}{}{}", Tags(self, level, true), prefix, value),
+                        )
+                        .build(),
+                );
+            }
+            return;
+        }
+        log::log!(
+            target: self.target(),
+            level,
+            "{}{}{}",
+            Tags(self, level, true),
+            prefix,
+            value
+        );
+    }
+    /// Log a record with the given key-values and the context attached, checking the level like [`LogConfig::dispatch`]
+    #[cfg(all(feature = "kv", not(feature = "tracing")))]
+    fn dispatch_key_values(
+        &self,
+        level: Level,
+        prefix: &str,
+        value: Arguments,
+        key_values: Option<&dyn log::kv::Source>,
+    ) {
+        if level <= log::STATIC_MAX_LEVEL && level <= log::max_level() {
+            let sources: [&dyn log::kv::Source; 2] = [&key_values, &self.context.as_slice()];
+            log::logger().log(
+                &self
+                    .record(
+                        level,
+                        format_args!("{}{}{}", Tags(self, level, false), prefix, value),
+                    )
+                    .key_values(&sources)
+                    .build(),
+            );
+        }
+    }
+    /// `tracing` callsites require a constant level and target, so the level is matched here
+    /// and the configured target and source location are recorded as the `log.target`, `log.module_path`, `log.file`, and `log.line` fields, as named by `tracing-log`.
+    #[cfg(feature = "tracing")]
+    fn dispatch(&self, level: Level, prefix: &str, value: Arguments) {
+        let tags = Tags(self, level, true);
+        let target = self.target();
+        let location = self.location;
+        macro_rules! event {
+            ($level:expr) => {
+                tracing::event!(
+                    $level,
+                    log.target = target,
+                    log.module_path = location.map(|location| location.module_path),
+                    log.file = location.map(|location| location.file),
+                    log.line = location.map(|location| location.line),
+                    prefix = %format_args!("{}{}", tags, prefix),
+                    value = %value
+                )
+            };
+        }
+        match level {
+            Level::Error => event!(tracing::Level::ERROR),
+            Level::Warn => event!(tracing::Level::WARN),
+            Level::Info => event!(tracing::Level::INFO),
+            Level::Debug => event!(tracing::Level::DEBUG),
+            Level::Trace => event!(tracing::Level::TRACE),
+        }
+    }
+    /// Flush the logger if `flush_every` log statements have been emitted since the last flush
+    #[cfg(not(feature = "tracing"))]
+    fn flush_if_due(&self) {
+        if self.flush_every > 0 {
+            let emitted = self.emitted.fetch_add(1, Ordering::Relaxed) + 1;
+            if emitted % self.flush_every == 0 {
+                log::logger().flush();
+            }
+        }
+    }
+    /// `tracing` subscribers have no equivalent of [`log::Log::flush`], so nothing is flushed
+    #[cfg(feature = "tracing")]
+    fn flush_if_due(&self) {}
+    fn is_enabled(&self) -> bool {
+        self.is_enabled_at(self.level)
+    }
+    #[cfg(not(feature = "tracing"))]
+    fn is_enabled_at(&self, level: Level) -> bool {
+        !self.off && log::log_enabled!(target: self.target(), self.remapped(level))
+    }
+    /// Like [`LogConfig::dispatch`], the level is matched so each level has a constant callsite for the subscriber to filter
+    #[cfg(feature = "tracing")]
+    fn is_enabled_at(&self, level: Level) -> bool {
+        !self.off
+            && match self.remapped(level) {
+                Level::Error => tracing::enabled!(tracing::Level::ERROR),
+                Level::Warn => tracing::enabled!(tracing::Level::WARN),
+                Level::Info => tracing::enabled!(tracing::Level::INFO),
+                Level::Debug => tracing::enabled!(tracing::Level::DEBUG),
+                Level::Trace => tracing::enabled!(tracing::Level::TRACE),
+            }
+    }
+    fn last_message(&self) -> Option<String> {
+        self.captured.as_ref().and_then(|captured| {
+            captured
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .clone()
+        })
+    }
+    /// Check if a log statement would be emitted, either to the logger or to the stderr fallback.
+    ///
+    /// This check is not atomic with emitting the statement: the maximum level may change before [`log::log`] checks it again.
+    /// That race is accepted, since it can only skip or filter a single statement and never affects the output of the service.
+    fn will_emit(&self) -> bool {
+        self.will_emit_at(self.level)
+    }
+    /// Check if a log statement at the given level would be emitted, like [`LogConfig::will_emit`]
+    fn will_emit_at(&self, level: Level) -> bool {
+        self.is_enabled_at(level) || (!self.off && self.falls_back())
+    }
+    fn set_filter(&mut self, filter: LevelFilter) {
+        match filter.to_level() {
+            Some(level) => {
+                self.level = level;
+                self.off = false;
+            }
+            None => self.off = true,
+        }
+    }
+    fn target(&self) -> &str {
+        match (&self.target, self.location) {
+            (Some(target), _) => target,
+            (None, Some(location)) => location.module_path,
+            (None, None) => module_path!(),
+        }
+    }
+    /// The level that a log statement at the given level is emitted at, as remapped by `remap_level`
+    fn remapped(&self, level: Level) -> Level {
+        match &self.remap {
+            Some(remap) => remap(level),
+            None => level,
+        }
+    }
+    /// The prefix to use when logging at the given level
+    fn prefix(&self, level: Level) -> &str {
+        self.level_prefixes.get(&level).unwrap_or(&self.prefix)
+    }
+}
+
+/// The source location that log statements are attributed to, as captured by [`log_display!`]
+#[derive(Clone, Copy)]
+struct Location {
+    module_path: &'static str,
+    file: &'static str,
+    line: u32,
+}
+
+/// How newlines in formatted input are handled
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Newlines {
+    Keep,
+    Split,
+    Strip,
+    Collapse,
+}
+
+impl<'a> Drop for LogConfig<'a> {
+    fn drop(&mut self) {
+        if let Some(message) = &self.drop_message {
+            self.log_prefixed("", format_args!("{}", message));
+        }
+    }
+}
+
+/// Formats the tags of a [`LogConfig`] that are logged before the prefix at the given level.
+///
+/// The context is formatted unless the flag is false, which is when it is attached to the record as key-values instead.
+struct Tags<'c, 'a>(&'c LogConfig<'a>, Level, bool);
+impl<'c, 'a> Display for Tags<'c, 'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.0.syslog_severity {
+            write!(f, "<{}>", syslog_severity(self.1))?;
+        }
+        if self.0.level_tag {
+            write!(f, "[{}] ", self.1)?;
+        }
+        if self.0.thread_info {
+            let thread = std::thread::current();
+            match thread.name() {
+                Some(name) => write!(f, "[{}] ", name)?,
+                None => write!(f, "[{:?}] ", thread.id())?,
+            }
+        }
+        if self.0.show_id {
+            write!(f, "#{} ", self.0.id)?;
+        }
+        if self.2 && !self.0.context.is_empty() {
+            f.write_str("[")?;
+            for (index, (key, value)) in self.0.context.iter().enumerate() {
+                if index > 0 {
+                    f.write_str(" ")?;
+                }
+                write!(f, "{}={}", key, value)?;
+            }
+            f.write_str("] ")?;
+        }
+        f.write_str(&self.0.default_prefix)
+    }
+}
+
+/// Formats key-values in the form of `[{key}={value} ...]`, for when they cannot be attached to the record
+#[cfg(feature = "kv")]
+struct KeyValues<'k>(&'k dyn log::kv::Source);
+#[cfg(feature = "kv")]
+impl<'k> Display for KeyValues<'k> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        struct Visitor<'f, 'w> {
+            f: &'f mut fmt::Formatter<'w>,
+            count: usize,
+        }
+        impl<'kvs, 'f, 'w> log::kv::VisitSource<'kvs> for Visitor<'f, 'w> {
+            fn visit_pair(
+                &mut self,
+                key: log::kv::Key<'kvs>,
+                value: log::kv::Value<'kvs>,
+            ) -> Result<(), log::kv::Error> {
+                let separator = if self.count == 0 { "[" } else { " " };
+                self.count += 1;
+                Ok(write!(self.f, "{}{}={}", separator, key, value)?)
+            }
+        }
+        let mut visitor = Visitor { f, count: 0 };
+        self.0.visit(&mut visitor).map_err(|_| fmt::Error)?;
+        if visitor.count > 0 {
+            visitor.f.write_str("]")?;
+        }
+        Ok(())
+    }
+}
+
+/// Pad the given buffer in place with spaces to at least `width` characters, like the `{:<width$}`, `{:>width$}`, and `{:^width$}` formats
+fn pad(buffer: &mut String, width: usize, alignment: fmt::Alignment) {
+    const SPACES: &str = "                                ";
+    let padding = width.saturating_sub(buffer.chars().count());
+    let before = match alignment {
+        fmt::Alignment::Left => 0,
+        fmt::Alignment::Right => padding,
+        fmt::Alignment::Center => padding / 2,
+    };
+    buffer.reserve(padding);
+    for _ in 0..padding - before {
+        buffer.push(' ');
+    }
+    let mut remaining = before;
+    while remaining > 0 {
+        let spaces = remaining.min(SPACES.len());
+        buffer.insert_str(0, &SPACES[..spaces]);
+        remaining -= spaces;
+    }
+}
+
+/// Formats the given parts separated by a single space, such as the lines of a string, without joining them into a [`String`]
+struct Joined<I>(I);
+impl<'s, I: Iterator<Item = &'s str> + Clone> Display for Joined<I> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, part) in self.0.clone().enumerate() {
+            if index > 0 {
+                f.write_str(" ")?;
+            }
+            f.write_str(part)?;
+        }
+        Ok(())
+    }
+}
+
+/// A [`fmt::Write`] that retains at most `max_len` characters, aborting formatting once they have been written
+struct TruncatingWriter<'b> {
+    buf: &'b mut String,
+    remaining: usize,
+    truncated: bool,
+}
+impl<'b> TruncatingWriter<'b> {
+    fn new(buf: &'b mut String, max_len: usize) -> Self {
+        Self {
+            buf,
+            remaining: max_len,
+            truncated: false,
+        }
+    }
+    /// Finish writing, appending a trailing `…` if it was truncated
+    fn finish(self) {
+        if self.truncated {
+            self.buf.push('…');
+        }
+    }
+}
+impl<'b> fmt::Write for TruncatingWriter<'b> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        match s.char_indices().nth(self.remaining) {
+            Some((index, _)) => {
+                self.buf.push_str(&s[..index]);
+                self.remaining = 0;
+                self.truncated = true;
+                Err(fmt::Error)
+            }
+            None => {
+                self.buf.push_str(s);
+                self.remaining -= s.chars().count();
+                Ok(())
+            }
+        }
+    }
+}
@@ -0,0 +1,36 @@
+//! Re-exports of all log services and their supporting types, along with [`Level`] and [`Service`].
+//!
+//! ```
+//! use sod_log::prelude::*;
+//!
+//! let logging_service = LogDisplayService::new(Level::Info, "my event: ");
+//! logging_service.process("hello world!").unwrap();
+//! ```
+
+#[doc(no_inline)]
+pub use log::{Level, LevelFilter};
+#[doc(no_inline)]
+pub use sod::Service;
+
+#[cfg(feature = "kv")]
+pub use crate::LogFieldsService;
+#[cfg(feature = "debug-tools")]
+pub use crate::LogPaceService;
+pub use crate::{
+    AdaptiveAlertLogResultService, CountingByLevelLogService, CyclicIndexLogService,
+    DeferredLogDisplayService, DurationUnit, DynamicPrefixLogDisplayService,
+    EpochLogDisplayService, EscalatingLogDisplayService, FormatStyle,
+    HeadThenSampleLogDisplayService, HeartbeatLogService, HistogramLogService, LevelCounts,
+    LogCase, LogChannelService, LogCloneTeeService, LogDebugService, LogDedupService,
+    LogDeltaService, LogDisplayService, LogDisplayServiceConst, LogDisplayServiceShared,
+    LogDistinctErrorService, LogDurationService, LogEachDisplayService, LogEscalateService,
+    LogFirstNService, LogHashService, LogIntoService, LogLogfmtService, LogMapService,
+    LogNumberService, LogOkFieldService, LogOptionalDebugService, LogOptionalDisplayService,
+    LogOrErrService, LogPairedLevelService, LogPreviewService, LogRateService, LogService, LogSink,
+    LogSinkService, LogSizeService, LogSlowService, LogTeeService, LogThenMapService,
+    LogTimedService, LogTransitionService, LogTupleService, LogUtf8LossyService, LogVariantService,
+    LogWriter, Loggable, NoLog, OptionFillRateLogService, PrefixError, RateFormat,
+    RenderLogService, Renderer, RingBufferLogDisplayService, SuppressibleLogService,
+    SwappablePrefixLogDisplayService, TeeLogDisplayService, ThresholdCrossingLogService,
+    WatchLogDisplayService,
+};
@@ -0,0 +1,2160 @@
+//! Tests of the log services, which capture the records they emit per thread, so tests may run in parallel.
+
+use super::*;
+use std::cell::{Cell, RefCell};
+use std::sync::Once;
+
+/// A record captured from a log service
+#[derive(Debug, Clone, PartialEq)]
+struct Captured {
+    level: Level,
+    target: String,
+    message: String,
+    module_path: Option<String>,
+    file: Option<String>,
+    line: Option<u32>,
+    #[cfg(feature = "kv")]
+    key_values: Vec<(String, KvValue)>,
+}
+
+/// A key-value captured with the type it was attached as
+#[cfg(feature = "kv")]
+#[derive(Debug, Clone, PartialEq)]
+enum KvValue {
+    Bool(bool),
+    I64(i64),
+    F64(f64),
+    Str(String),
+}
+
+thread_local! {
+    static CAPTURED: RefCell<Vec<Captured>> = const { RefCell::new(Vec::new()) };
+    static MAX_LEVEL: Cell<LevelFilter> = const { Cell::new(LevelFilter::Trace) };
+    static FLUSHES: Cell<u64> = const { Cell::new(0) };
+}
+
+/// Set in the environment of a child process that runs a single test without a logger installed
+const CHILD: &str = "SOD_LOG_TEST_CHILD";
+
+/// A [`log::Log`] that captures records logged by the current thread, up to the thread's maximum level
+struct CaptureLogger;
+impl log::Log for CaptureLogger {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        metadata.level() <= MAX_LEVEL.with(Cell::get)
+    }
+    fn log(&self, record: &log::Record) {
+        if self.enabled(record.metadata()) {
+            let captured = Captured {
+                level: record.level(),
+                target: record.target().to_owned(),
+                message: record.args().to_string(),
+                module_path: record.module_path().map(str::to_owned),
+                file: record.file().map(str::to_owned),
+                line: record.line(),
+                #[cfg(feature = "kv")]
+                key_values: key_values(record.key_values()),
+            };
+            CAPTURED.with(|records| records.borrow_mut().push(captured));
+        }
+    }
+    fn flush(&self) {
+        FLUSHES.with(|flushes| flushes.set(flushes.get() + 1));
+    }
+}
+
+/// Collect the key-values of a record
+#[cfg(feature = "kv")]
+fn key_values(source: &dyn log::kv::Source) -> Vec<(String, KvValue)> {
+    struct Collect(Vec<(String, KvValue)>);
+    impl<'kvs> log::kv::VisitSource<'kvs> for Collect {
+        fn visit_pair(
+            &mut self,
+            key: log::kv::Key<'kvs>,
+            value: log::kv::Value<'kvs>,
+        ) -> Result<(), log::kv::Error> {
+            let value = if let Some(value) = value.to_bool() {
+                KvValue::Bool(value)
+            } else if let Some(value) = value.to_i64() {
+                KvValue::I64(value)
+            } else if let Some(value) = value.to_f64() {
+                KvValue::F64(value)
+            } else {
+                KvValue::Str(value.to_string())
+            };
+            self.0.push((key.to_string(), value));
+            Ok(())
+        }
+    }
+    let mut collect = Collect(Vec::new());
+    source.visit(&mut collect).unwrap();
+    collect.0
+}
+
+/// A [`tracing::Subscriber`] that captures events of the current thread like [`CaptureLogger`], which is set as the default for the duration of [`capture`]
+#[cfg(feature = "tracing")]
+struct CaptureSubscriber;
+#[cfg(feature = "tracing")]
+impl tracing::Subscriber for CaptureSubscriber {
+    fn register_callsite(
+        &self,
+        _: &'static tracing::Metadata<'static>,
+    ) -> tracing::subscriber::Interest {
+        // the maximum level is per thread, so every event is checked with `enabled`
+        tracing::subscriber::Interest::sometimes()
+    }
+    fn enabled(&self, metadata: &tracing::Metadata) -> bool {
+        level_of(metadata.level()) <= MAX_LEVEL.with(Cell::get)
+    }
+    fn new_span(&self, _: &tracing::span::Attributes) -> tracing::span::Id {
+        tracing::span::Id::from_u64(1)
+    }
+    fn record(&self, _: &tracing::span::Id, _: &tracing::span::Record) {}
+    fn record_follows_from(&self, _: &tracing::span::Id, _: &tracing::span::Id) {}
+    fn event(&self, event: &tracing::Event) {
+        #[derive(Default)]
+        struct Fields {
+            target: Option<String>,
+            module_path: Option<String>,
+            file: Option<String>,
+            line: Option<u32>,
+            prefix: String,
+            value: String,
+        }
+        impl tracing::field::Visit for Fields {
+            fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn fmt::Debug) {
+                let value = format!("{:?}", value);
+                match field.name() {
+                    "log.target" => self.target = Some(value),
+                    "log.module_path" => self.module_path = Some(value),
+                    "log.file" => self.file = Some(value),
+                    "log.line" => self.line = value.parse().ok(),
+                    "prefix" => self.prefix = value,
+                    "value" => self.value = value,
+                    _ => {}
+                }
+            }
+            fn record_str(&mut self, field: &tracing::field::Field, value: &str) {
+                self.record_debug(field, &format_args!("{}", value))
+            }
+        }
+        let metadata = event.metadata();
+        let mut fields = Fields::default();
+        event.record(&mut fields);
+        let captured = Captured {
+            level: level_of(metadata.level()),
+            target: fields
+                .target
+                .unwrap_or_else(|| metadata.target().to_owned()),
+            message: fields.prefix + &fields.value,
+            module_path: fields.module_path,
+            file: fields.file,
+            line: fields.line,
+            #[cfg(feature = "kv")]
+            key_values: Vec::new(),
+        };
+        CAPTURED.with(|records| records.borrow_mut().push(captured));
+    }
+    fn enter(&self, _: &tracing::span::Id) {}
+    fn exit(&self, _: &tracing::span::Id) {}
+}
+
+/// Convert a [`tracing::Level`] to the equivalent [`Level`]
+#[cfg(feature = "tracing")]
+fn level_of(level: &tracing::Level) -> Level {
+    match *level {
+        tracing::Level::ERROR => Level::Error,
+        tracing::Level::WARN => Level::Warn,
+        tracing::Level::INFO => Level::Info,
+        tracing::Level::DEBUG => Level::Debug,
+        _ => Level::Trace,
+    }
+}
+
+/// Install the capturing logger, which is shared by every test
+fn init() {
+    static INIT: Once = Once::new();
+    INIT.call_once(|| {
+        log::set_logger(&CaptureLogger).unwrap();
+        log::set_max_level(LevelFilter::Trace);
+    });
+}
+
+/// Set the maximum level captured from the current thread, which is reset to [`LevelFilter::Trace`] by [`capture`]
+fn set_max_level(filter: LevelFilter) {
+    MAX_LEVEL.with(|max_level| max_level.set(filter));
+}
+
+/// Run the given function and return the records it logged
+fn capture<F: FnOnce()>(f: F) -> Vec<Captured> {
+    init();
+    CAPTURED.with(|records| records.borrow_mut().clear());
+    set_max_level(LevelFilter::Trace);
+    FLUSHES.with(|flushes| flushes.set(0));
+    #[cfg(not(feature = "tracing"))]
+    f();
+    #[cfg(feature = "tracing")]
+    tracing::subscriber::with_default(CaptureSubscriber, f);
+    CAPTURED.with(|records| records.take())
+}
+
+/// Run the given function and return the messages it logged
+fn messages<F: FnOnce()>(f: F) -> Vec<String> {
+    capture(f)
+        .into_iter()
+        .map(|captured| captured.message)
+        .collect()
+}
+
+/// Run the named test in a child process, where no logger is installed, and return its stderr
+fn stderr_of_child(test: &str) -> String {
+    let output = std::process::Command::new(std::env::current_exe().unwrap())
+        .args([test, "--exact", "--nocapture", "--test-threads=1"])
+        .env(CHILD, "1")
+        .output()
+        .unwrap();
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(output.status.success(), "{}", stderr);
+    stderr
+}
+
+/// Check if this is the child process started by [`stderr_of_child`]
+fn is_child() -> bool {
+    std::env::var_os(CHILD).is_some()
+}
+
+#[test]
+fn remap_level_changes_emitted_level() {
+    let records = capture(|| {
+        let service = LogDisplayService::info("value: ").remap_level(|level| match level {
+            Level::Info => Level::Trace,
+            level => level,
+        });
+        assert_eq!(service.process(1), Ok(1));
+    });
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0].level, Level::Trace);
+    assert_eq!(records[0].message, "value: 1");
+}
+
+#[test]
+fn remap_level_applies_to_a_level_replaced_later() {
+    let demote = |level| match level {
+        Level::Error => Level::Warn,
+        Level::Warn => Level::Info,
+        Level::Info => Level::Debug,
+        _ => Level::Trace,
+    };
+    let service = LogDisplayService::info("value: ")
+        .remap_level(demote)
+        .with_level(Level::Warn);
+    let records = capture(|| {
+        assert_eq!(service.process(1), Ok(1));
+        set_max_level(LevelFilter::Warn);
+        assert!(!service.is_enabled());
+        assert_eq!(service.process(2), Ok(2));
+    });
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0].level, Level::Info);
+    assert_eq!(records[0].message, "value: 1");
+}
+
+#[test]
+fn fallback_stderr_without_logger() {
+    if !is_child() {
+        let stderr = stderr_of_child("tests::fallback_stderr_without_logger");
+        assert!(stderr.contains("fallback: 1\n"), "{}", stderr);
+        assert!(!stderr.contains("dropped: 2"), "{}", stderr);
+        return;
+    }
+    let service = LogDisplayService::info("fallback: ").with_fallback_stderr();
+    assert_eq!(service.process(1), Ok(1));
+    assert_eq!(LogDisplayService::info("dropped: ").process(2), Ok(2));
+}
+
+#[test]
+fn drop_message_is_logged_during_thread_local_teardown() {
+    if !is_child() {
+        let stderr = stderr_of_child("tests::drop_message_is_logged_during_thread_local_teardown");
+        assert!(stderr.contains("teardown: 1\n"), "{}", stderr);
+        assert!(stderr.contains("done\n"), "{}", stderr);
+        return;
+    }
+    thread_local! {
+        static SERVICE: RefCell<Option<LogDisplayService<'static, u32>>> = const { RefCell::new(None) };
+    }
+    std::thread::spawn(|| {
+        // the service is accessed before the format buffer, so it is dropped after the buffer is destroyed
+        SERVICE.with(|service| {
+            let logging_service = LogDisplayService::info("teardown: ")
+                .with_fallback_stderr()
+                .with_trim()
+                .log_on_drop("done");
+            assert_eq!(logging_service.process(1), Ok(1));
+            *service.borrow_mut() = Some(logging_service);
+        });
+    })
+    .join()
+    .unwrap();
+}
+
+#[test]
+fn hash_is_stable_for_the_same_input() {
+    let service = LogHashService::info("hash: ");
+    let messages = messages(|| {
+        assert_eq!(service.process("payload"), Ok("payload"));
+        assert_eq!(service.process("payload"), Ok("payload"));
+        assert_eq!(service.process("other"), Ok("other"));
+    });
+    assert_eq!(messages.len(), 3);
+    assert_eq!(messages[0], messages[1]);
+    assert_ne!(messages[0], messages[2]);
+    assert_eq!(messages[0].len(), "hash: ".len() + 16);
+}
+
+#[test]
+fn max_len_truncates_with_ellipsis() {
+    let service = LogDisplayService::info("long: ").with_max_len(5);
+    let messages = messages(|| {
+        assert_eq!(service.process("x".repeat(10_000)).unwrap().len(), 10_000);
+        assert_eq!(service.process("short".to_owned()).unwrap(), "short");
+    });
+    assert_eq!(messages, vec!["long: xxxxx…", "long: short"]);
+}
+
+#[test]
+fn prefix_for_selects_prefix_by_level() {
+    let service = LogDisplayService::info("info: ").prefix_for(Level::Error, "ERROR! ");
+    let records = capture(|| {
+        assert_eq!(service.process(1), Ok(1));
+        let service = service.with_level(Level::Error);
+        assert_eq!(service.process(2), Ok(2));
+    });
+    let records: Vec<_> = records
+        .into_iter()
+        .map(|captured| (captured.level, captured.message))
+        .collect();
+    assert_eq!(
+        records,
+        vec![
+            (Level::Info, "info: 1".to_owned()),
+            (Level::Error, "ERROR! 2".to_owned())
+        ]
+    );
+}
+
+#[test]
+fn prefix_for_is_not_applied_to_unprefixed_statements() {
+    let messages = messages(|| {
+        drop(
+            LogDisplayService::<u32>::info("info: ")
+                .prefix_for(Level::Info, "INFO! ")
+                .log_on_drop("dropped"),
+        )
+    });
+    assert_eq!(messages, vec!["dropped"]);
+}
+
+#[test]
+fn tee_logs_to_both_services() {
+    let service = LogTeeService::new(
+        LogDisplayService::debug("file: ").with_target("file"),
+        LogDisplayService::warn("stderr: ").with_target("stderr"),
+    );
+    let records = capture(|| assert_eq!(service.process(7), Ok(7)));
+    let records: Vec<_> = records
+        .into_iter()
+        .map(|captured| (captured.level, captured.target, captured.message))
+        .collect();
+    assert_eq!(
+        records,
+        vec![
+            (Level::Debug, "file".to_owned(), "file: 7".to_owned()),
+            (Level::Warn, "stderr".to_owned(), "stderr: 7".to_owned())
+        ]
+    );
+}
+
+#[test]
+fn counting_by_level_counts_emitted_statements() {
+    let service = CountingByLevelLogService::new(LogDisplayService::info("count: "));
+    let logged = messages(|| {
+        for input in 0..3 {
+            assert_eq!(service.process(input), Ok(input));
+        }
+        set_max_level(LevelFilter::Warn);
+        assert_eq!(service.process(3), Ok(3));
+    });
+    assert_eq!(logged.len(), 3);
+    assert_eq!(
+        service.counts(),
+        LevelCounts {
+            info: 3,
+            ..LevelCounts::default()
+        }
+    );
+    let service = CountingByLevelLogService::new(
+        LogDisplayService::info("count: ").with_filter(LevelFilter::Off),
+    );
+    assert!(messages(|| assert_eq!(service.process(0), Ok(0))).is_empty());
+    assert_eq!(service.counts(), LevelCounts::default());
+}
+
+#[test]
+fn is_enabled_reflects_max_level() {
+    capture(|| {
+        set_max_level(LevelFilter::Info);
+        assert!(LogDisplayService::<u32>::error("").is_enabled());
+        assert!(LogDisplayService::<u32>::info("").is_enabled());
+        assert!(!LogDisplayService::<u32>::debug("").is_enabled());
+        assert!(!LogDebugService::<u32>::trace("").is_enabled());
+        assert!(!LogDisplayService::<u32>::error("")
+            .with_filter(LevelFilter::Off)
+            .is_enabled());
+        set_max_level(LevelFilter::Off);
+        assert!(!LogDisplayService::<u32>::error("").is_enabled());
+    });
+}
+
+mod caller {
+    pub fn log_here() -> crate::LogDisplayService<'static, u32> {
+        crate::log_here!(info, "here: ")
+    }
+    pub const LINE: u32 = line!() + 2;
+    pub fn log_display() -> crate::LogDisplayService<'static, u32> {
+        crate::log_display!(info, "display: ")
+    }
+}
+
+#[test]
+fn log_here_targets_caller_module() {
+    let records = capture(|| assert_eq!(caller::log_here().process(1), Ok(1)));
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0].target, concat!(module_path!(), "::caller"));
+    assert_eq!(records[0].message, "here: 1");
+}
+
+#[test]
+fn each_logs_indexed_elements() {
+    let messages = messages(|| {
+        let service = LogEachDisplayService::info("element");
+        assert_eq!(
+            service.process(vec!["a", "b", "c"]),
+            Ok(vec!["a", "b", "c"])
+        );
+        let service = LogEachDisplayService::info("capped").with_max_elements(1);
+        assert_eq!(service.process(vec![1, 2]), Ok(vec![1, 2]));
+    });
+    assert_eq!(
+        messages,
+        vec![
+            "element[0] a",
+            "element[1] b",
+            "element[2] c",
+            "capped[0] 1"
+        ]
+    );
+}
+
+#[test]
+fn slow_logs_only_when_threshold_is_exceeded() {
+    let fast = LogSlowService::warn(
+        "fast: ",
+        Duration::from_secs(60),
+        FnService::new(|input: u32| Ok::<_, ()>(input + 1)),
+    );
+    let slow = LogSlowService::warn(
+        "slow: ",
+        Duration::from_millis(1),
+        FnService::new(|input: u32| {
+            std::thread::sleep(Duration::from_millis(20));
+            Err::<u32, _>(input)
+        }),
+    );
+    let messages = messages(|| {
+        assert_eq!(fast.process(1), Ok(2));
+        assert_eq!(slow.process(1), Err(1));
+    });
+    assert_eq!(messages.len(), 1);
+    assert!(messages[0].starts_with("slow: "), "{}", messages[0]);
+}
+
+#[test]
+fn on_none_is_invoked_for_each_none() {
+    let display_nones = Arc::new(AtomicU64::new(0));
+    let debug_nones = Arc::new(AtomicU64::new(0));
+    let display = LogOptionalDisplayService::info("display: ").with_on_none({
+        let display_nones = Arc::clone(&display_nones);
+        move || {
+            display_nones.fetch_add(1, Ordering::Relaxed);
+        }
+    });
+    let debug = LogOptionalDebugService::info("debug: ").with_on_none({
+        let debug_nones = Arc::clone(&debug_nones);
+        move || {
+            debug_nones.fetch_add(1, Ordering::Relaxed);
+        }
+    });
+    let messages = messages(|| {
+        for input in [None, Some(1), None] {
+            assert_eq!(display.process(input), Ok(input));
+            assert_eq!(debug.process(input), Ok(input));
+        }
+    });
+    assert_eq!(messages, vec!["display: 1", "debug: 1"]);
+    assert_eq!(display_nones.load(Ordering::Relaxed), 2);
+    assert_eq!(debug_nones.load(Ordering::Relaxed), 2);
+}
+
+#[test]
+fn with_level_overrides_constructed_level() {
+    let records = capture(|| {
+        let display = LogDisplayService::info("").with_level(Level::Warn);
+        assert_eq!(display.process(1), Ok(1));
+        let debug = LogDebugService::info("").with_level(Level::Warn);
+        assert_eq!(debug.process(2), Ok(2));
+        let optional_display = LogOptionalDisplayService::info("").with_level(Level::Warn);
+        assert_eq!(optional_display.process(Some(3)), Ok(Some(3)));
+        let optional_debug = LogOptionalDebugService::info("").with_level(Level::Warn);
+        assert_eq!(optional_debug.process(Some(4)), Ok(Some(4)));
+    });
+    let records: Vec<_> = records
+        .into_iter()
+        .map(|captured| (captured.level, captured.message))
+        .collect();
+    assert_eq!(
+        records,
+        vec![
+            (Level::Warn, "1".to_owned()),
+            (Level::Warn, "2".to_owned()),
+            (Level::Warn, "3".to_owned()),
+            (Level::Warn, "4".to_owned())
+        ]
+    );
+}
+
+#[test]
+fn const_level_service_logs_at_its_level() {
+    let records = capture(|| {
+        let service = LogDisplayServiceConst::<_, { Level::Info as usize }>::new("const: ");
+        assert_eq!(service.process(1), Ok(1));
+    });
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0].level, Level::Info);
+    assert_eq!(records[0].message, "const: 1");
+}
+
+#[test]
+fn channel_sends_emitted_statements() {
+    let (sender, receiver) = std::sync::mpsc::channel();
+    let service = LogChannelService::info("channel: ", sender);
+    let records = capture(|| {
+        assert_eq!(service.process(1), Ok(1));
+        set_max_level(LevelFilter::Warn);
+        assert_eq!(service.process(2), Ok(2));
+    });
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0].message, "channel: 1");
+    assert_eq!(receiver.try_iter().collect::<Vec<_>>(), vec!["channel: 1"]);
+
+    drop(receiver);
+    let records = capture(|| {
+        assert_eq!(service.process(3), Ok(3));
+        assert_eq!(service.process(4), Ok(4));
+    });
+    let records: Vec<_> = records
+        .into_iter()
+        .map(|captured| (captured.level, captured.message))
+        .collect();
+    assert_eq!(
+        records,
+        vec![
+            (Level::Info, "channel: 3".to_owned()),
+            (
+                Level::Warn,
+                "channel: channel disconnected, log statements will no longer be sent".to_owned()
+            ),
+            (Level::Info, "channel: 4".to_owned())
+        ]
+    );
+}
+
+#[test]
+fn sequence_numbers_each_statement() {
+    let service = LogDisplayService::info("event").with_sequence();
+    let messages = messages(|| {
+        for input in ["a", "b", "c"] {
+            assert_eq!(service.process(input), Ok(input));
+        }
+    });
+    assert_eq!(messages, vec!["event#0 a", "event#1 b", "event#2 c"]);
+}
+
+#[test]
+fn prefix_fn_is_called_for_each_input() {
+    let calls = AtomicU64::new(0);
+    let service = LogDisplayService::from_prefix_fn(Level::Info, || {
+        match calls.fetch_add(1, Ordering::Relaxed) {
+            0 => Cow::Borrowed("first: "),
+            call => Cow::Owned(format!("call {}: ", call)),
+        }
+    });
+    let messages = messages(|| {
+        assert_eq!(service.process("a"), Ok("a"));
+        assert_eq!(service.process("b"), Ok("b"));
+    });
+    assert_eq!(messages, vec!["first: a", "call 1: b"]);
+}
+
+#[test]
+fn prefix_braces_are_logged_verbatim() {
+    let service = LogDisplayService::try_new(Level::Info, "{} {0} {:?}: ").unwrap();
+    let messages = messages(|| assert_eq!(service.process(1), Ok(1)));
+    assert_eq!(messages, vec!["{} {0} {:?}: 1"]);
+}
+
+#[test]
+fn try_new_rejects_control_characters() {
+    assert_eq!(
+        LogDisplayService::<u32>::try_new(Level::Info, "line\nbreak: ").err(),
+        Some(PrefixError::ControlCharacter('\n'))
+    );
+    assert_eq!(
+        LogDebugService::<u32>::try_new(Level::Info, "bell\u{7}: ").err(),
+        Some(PrefixError::ControlCharacter('\u{7}'))
+    );
+}
+
+#[test]
+fn head_then_sample_logs_head_and_sampled_tail() {
+    let service = HeadThenSampleLogDisplayService::info("", 3, 4);
+    let messages = messages(|| {
+        for input in 0..12 {
+            assert_eq!(service.process(input), Ok(input));
+        }
+    });
+    assert_eq!(messages, vec!["0", "1", "2", "3", "7", "11"]);
+}
+
+#[test]
+fn fallback_stderr_writes_formatted_statements() {
+    if !is_child() {
+        let stderr = stderr_of_child("tests::fallback_stderr_writes_formatted_statements");
+        assert!(stderr.contains("[WARN] debug: \"a\"\n"), "{}", stderr);
+        assert!(stderr.contains("optional: 2\n"), "{}", stderr);
+        let records = capture(|| {
+            let service = LogDisplayService::info("logger: ").with_fallback_stderr();
+            assert_eq!(service.process(3), Ok(3));
+        });
+        assert_eq!(records.len(), 1);
+        return;
+    }
+    let debug = LogDebugService::warn("debug: ")
+        .with_level_tag()
+        .with_fallback_stderr();
+    assert_eq!(debug.process("a"), Ok("a"));
+    let optional = LogOptionalDisplayService::info("optional: ").with_fallback_stderr();
+    assert_eq!(optional.process(Some(2)), Ok(Some(2)));
+}
+
+#[test]
+fn set_prefix_applies_to_subsequent_statements() {
+    let service = LogDisplayService::info("phase1: ").with_swappable_prefix();
+    let messages = messages(|| {
+        assert_eq!(service.process(1), Ok(1));
+        service.set_prefix("phase2: ");
+        assert_eq!(service.process(2), Ok(2));
+    });
+    assert_eq!(messages, vec!["phase1: 1", "phase2: 2"]);
+    assert_eq!(&*service.prefix(), "phase2: ");
+}
+
+#[test]
+fn swappable_prefix_keeps_counting_while_disabled() {
+    let counter = Arc::new(AtomicU64::new(0));
+    let service = LogDisplayService::info("phase1: ")
+        .with_sequence()
+        .with_counter(Arc::clone(&counter))
+        .with_swappable_prefix();
+    let messages = messages(|| {
+        set_max_level(LevelFilter::Warn);
+        assert_eq!(service.process("a"), Ok("a"));
+        set_max_level(LevelFilter::Info);
+        service.set_prefix("phase2: ");
+        assert_eq!(service.process("b"), Ok("b"));
+    });
+    assert_eq!(messages, vec!["phase2: #1 b"]);
+    assert_eq!(counter.load(Ordering::Relaxed), 2);
+}
+
+/// Input that replaces the prefix of the service that logs it while it is formatted
+struct SetsPrefix<'s>(&'s SwappablePrefixLogDisplayService<'static, SetsPrefix<'s>>);
+impl Display for SetsPrefix<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.set_prefix("replaced: ");
+        f.write_str("input")
+    }
+}
+
+#[test]
+fn set_prefix_from_display_does_not_deadlock() {
+    let service = LogDisplayService::info("original: ").with_swappable_prefix();
+    let messages = messages(|| {
+        assert!(service.process(SetsPrefix(&service)).is_ok());
+        assert!(service.process(SetsPrefix(&service)).is_ok());
+    });
+    assert_eq!(messages, vec!["original: input", "replaced: input"]);
+}
+
+#[test]
+fn getters_reflect_constructor_arguments() {
+    let debug = LogDebugService::<u32>::warn("debug: ");
+    assert_eq!((debug.level(), debug.prefix()), (Level::Warn, "debug: "));
+    let optional_debug = LogOptionalDebugService::<u32>::trace("optional debug: ");
+    assert_eq!(
+        (optional_debug.level(), optional_debug.prefix()),
+        (Level::Trace, "optional debug: ")
+    );
+    let display = LogDisplayService::<u32>::error("display: ");
+    assert_eq!(
+        (display.level(), display.prefix()),
+        (Level::Error, "display: ")
+    );
+    let optional_display = LogOptionalDisplayService::<u32>::debug("optional display: ");
+    assert_eq!(
+        (optional_display.level(), optional_display.prefix()),
+        (Level::Debug, "optional display: ")
+    );
+}
+
+#[test]
+fn watch_logs_only_the_watched_value() {
+    let service = WatchLogDisplayService::info("watched: ", 3);
+    let messages = messages(|| {
+        for input in 1..5 {
+            assert_eq!(service.process(input), Ok(input));
+        }
+    });
+    assert_eq!(messages, vec!["watched: 3"]);
+}
+
+#[test]
+fn split_lines_logs_each_line() {
+    let service = LogDisplayService::info("split: ").with_split_lines();
+    let messages = messages(|| assert_eq!(service.process("a\nb"), Ok("a\nb")));
+    assert_eq!(messages, vec!["split: a", "split: b"]);
+}
+
+#[test]
+fn strip_newlines_logs_a_single_line() {
+    let service = LogDisplayService::info("strip: ").with_strip_newlines();
+    let messages = messages(|| assert_eq!(service.process("a\nb"), Ok("a\nb")));
+    assert_eq!(messages, vec!["strip: a b"]);
+}
+
+#[test]
+fn trim_removes_trailing_newline_from_value() {
+    let service = LogDisplayService::info("  trimmed:  ").with_trim();
+    let messages = messages(|| assert_eq!(service.process("value \n"), Ok("value \n")));
+    assert_eq!(messages, vec!["  trimmed:  value"]);
+}
+
+#[test]
+fn log_service_formats_with_each_style() {
+    let debug = LogService::debug(Level::Info, "debug: ");
+    let pretty_debug = LogService::pretty_debug(Level::Info, "pretty: ");
+    let display = LogService::display(Level::Info, "display: ");
+    assert_eq!(debug.style(), FormatStyle::Debug);
+    assert_eq!(pretty_debug.style(), FormatStyle::PrettyDebug);
+    assert_eq!(display.style(), FormatStyle::Display);
+    let messages = messages(|| {
+        assert_eq!(debug.process("a"), Ok("a"));
+        assert_eq!(pretty_debug.process(vec![1]), Ok(vec![1]));
+        assert_eq!(display.process("a"), Ok("a"));
+    });
+    assert_eq!(
+        messages,
+        vec!["debug: \"a\"", "pretty: [\n    1,\n]", "display: a"]
+    );
+}
+
+#[test]
+fn heartbeat_logs_once_per_interval() {
+    let service = HeartbeatLogService::info("heartbeat: ", Duration::from_secs(60));
+    let rewind = |by: Duration| {
+        let mut last_heartbeat = service.last_heartbeat.lock().unwrap();
+        *last_heartbeat -= by;
+    };
+    let messages = messages(|| {
+        assert_eq!(service.process(1), Ok(1));
+        rewind(Duration::from_secs(61));
+        assert_eq!(service.process(2), Ok(2));
+        assert_eq!(service.process(3), Ok(3));
+        rewind(Duration::from_secs(30));
+        assert_eq!(service.process(4), Ok(4));
+        rewind(Duration::from_secs(31));
+        assert_eq!(service.process(5), Ok(5));
+    });
+    assert_eq!(messages, vec!["heartbeat: alive", "heartbeat: alive"]);
+}
+
+#[test]
+fn timed_logs_elapsed_time_of_inner_service() {
+    let service = LogTimedService::info(
+        "sleep",
+        FnService::new(|input: u32| {
+            std::thread::sleep(Duration::from_millis(20));
+            Ok::<_, ()>(input)
+        }),
+    );
+    let messages = messages(|| assert_eq!(service.process(1), Ok(1)));
+    assert_eq!(messages.len(), 1);
+    let elapsed = messages[0].strip_prefix("sleep took ").unwrap();
+    let elapsed = match elapsed.strip_suffix("ms") {
+        Some(millis) => Duration::from_secs_f64(millis.parse::<f64>().unwrap() / 1000.0),
+        None => Duration::from_secs_f64(elapsed.strip_suffix('s').unwrap().parse().unwrap()),
+    };
+    assert!(elapsed >= Duration::from_millis(20), "{:?}", elapsed);
+    assert!(elapsed < Duration::from_secs(5), "{:?}", elapsed);
+}
+
+#[test]
+fn case_transforms_only_the_logged_value() {
+    let messages = messages(|| {
+        for case in [LogCase::Upper, LogCase::Lower, LogCase::AsIs] {
+            let service = LogDisplayService::info("Case: ").with_case(case);
+            assert_eq!(service.process("MiXeD"), Ok("MiXeD"));
+        }
+    });
+    assert_eq!(messages, vec!["Case: MIXED", "Case: mixed", "Case: MiXeD"]);
+}
+
+#[cfg(feature = "kv")]
+#[test]
+fn fields_are_attached_as_key_values() {
+    let service = LogFieldsService::info("event: ", |input: &(u32, &str)| {
+        vec![
+            ("user_id", input.0.to_string()),
+            ("action", input.1.to_owned()),
+        ]
+    });
+    let records = capture(|| assert_eq!(service.process((42, "login")), Ok((42, "login"))));
+    assert_eq!(records.len(), 1);
+    if cfg!(feature = "tracing") {
+        assert_eq!(records[0].message, "event: [user_id=42 action=login]");
+    } else {
+        assert_eq!(records[0].message, "event: ");
+        assert_eq!(
+            records[0].key_values,
+            vec![
+                ("user_id".to_owned(), KvValue::Str("42".to_owned())),
+                ("action".to_owned(), KvValue::Str("login".to_owned()))
+            ]
+        );
+    }
+}
+
+#[cfg(not(feature = "tracing"))]
+#[test]
+fn flush_every_flushes_at_cadence() {
+    let service = LogDisplayService::info("").with_flush_every(2);
+    let mut flushes = Vec::new();
+    capture(|| {
+        for input in 0..5 {
+            assert_eq!(service.process(input), Ok(input));
+            flushes.push(FLUSHES.with(Cell::get));
+        }
+    });
+    assert_eq!(flushes, vec![0, 1, 1, 2, 2]);
+}
+
+#[test]
+fn filter_off_disables_logging() {
+    let records = capture(|| {
+        let off = LogDisplayService::info("off: ").with_filter(LevelFilter::Off);
+        assert_eq!(off.process(1), Ok(1));
+        assert!(!off.is_enabled());
+        let debug = LogDisplayService::info("debug: ").with_filter(LevelFilter::Debug);
+        assert_eq!(debug.process(2), Ok(2));
+    });
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0].level, Level::Debug);
+    assert_eq!(records[0].message, "debug: 2");
+}
+
+#[test]
+fn display_summarizes_configuration() {
+    assert_eq!(
+        LogDisplayService::<u32>::info("event: ").to_string(),
+        "LogDisplayService(level=INFO, prefix=\"event: \")"
+    );
+    assert_eq!(
+        LogDebugService::<u32>::warn("debug: ").to_string(),
+        "LogDebugService(level=WARN, prefix=\"debug: \")"
+    );
+    assert_eq!(
+        LogOptionalDisplayService::<u32>::error("").to_string(),
+        "LogOptionalDisplayService(level=ERROR, prefix=\"\")"
+    );
+    assert_eq!(
+        LogOptionalDebugService::<u32>::trace("x")
+            .with_filter(LevelFilter::Off)
+            .to_string(),
+        "LogOptionalDebugService(level=OFF, prefix=\"x\")"
+    );
+}
+
+#[test]
+fn static_and_closure_prefixes() {
+    let owned = LogDisplayService::new(Level::Info, String::from("owned: "));
+    let closure = LogDisplayService::from_prefix_fn(Level::Info, || Cow::Borrowed("closure: "));
+    let messages = messages(|| {
+        assert_eq!(owned.process(1), Ok(1));
+        assert_eq!(closure.process(2), Ok(2));
+    });
+    assert_eq!(messages, vec!["owned: 1", "closure: 2"]);
+}
+
+#[test]
+fn tee_display_logs_and_sends_input() {
+    let (sender, receiver) = std::sync::mpsc::channel();
+    let service = TeeLogDisplayService::info("tee: ", sender);
+    let messages = messages(|| assert_eq!(service.process(1), Ok(1)));
+    assert_eq!(messages, vec!["tee: 1"]);
+    assert_eq!(receiver.try_iter().collect::<Vec<_>>(), vec![1]);
+
+    drop(receiver);
+    let records = capture(|| {
+        assert_eq!(service.process(2), Ok(2));
+        assert_eq!(service.process(3), Ok(3));
+    });
+    let records: Vec<_> = records
+        .into_iter()
+        .map(|captured| (captured.level, captured.message))
+        .collect();
+    assert_eq!(
+        records,
+        vec![
+            (Level::Info, "tee: 2".to_owned()),
+            (
+                Level::Warn,
+                "tee: channel disconnected, input will no longer be sent".to_owned()
+            ),
+            (Level::Info, "tee: 3".to_owned())
+        ]
+    );
+}
+
+#[test]
+fn tee_display_does_not_send_input_while_disabled() {
+    let (sender, receiver) = std::sync::mpsc::channel();
+    let service = TeeLogDisplayService::debug("tee: ", sender);
+    let messages = messages(|| {
+        set_max_level(LevelFilter::Info);
+        assert_eq!(service.process(1), Ok(1));
+        set_max_level(LevelFilter::Debug);
+        assert_eq!(service.process(2), Ok(2));
+    });
+    assert_eq!(messages, vec!["tee: 2"]);
+    assert_eq!(receiver.try_iter().collect::<Vec<_>>(), vec![2]);
+}
+
+#[test]
+fn from_env_parses_level() {
+    std::env::set_var("SOD_LOG_TEST_FROM_ENV_TRACE", "trace");
+    std::env::set_var("SOD_LOG_TEST_FROM_ENV_INVALID", "loud");
+    let service =
+        LogDisplayService::<u32>::from_env("SOD_LOG_TEST_FROM_ENV_TRACE", "env: ").unwrap();
+    assert_eq!(service.level(), Level::Trace);
+    assert!(LogDisplayService::<u32>::from_env("SOD_LOG_TEST_FROM_ENV_INVALID", "env: ").is_err());
+    let service = LogDebugService::<u32>::from_env("SOD_LOG_TEST_FROM_ENV_UNSET", "env: ").unwrap();
+    assert_eq!(service.level(), Level::Info);
+}
+
+#[test]
+fn from_filter_off_is_a_no_op() {
+    let messages = messages(|| {
+        let off = LogOptionalDisplayService::from_filter(LevelFilter::Off, "off: ");
+        assert_eq!(off.process(Some(1)), Ok(Some(1)));
+        let info = LogOptionalDisplayService::from_filter(LevelFilter::Info, "info: ");
+        assert_eq!(info.process(Some(2)), Ok(Some(2)));
+    });
+    assert_eq!(messages, vec!["info: 2"]);
+}
+
+#[test]
+fn utf8_lossy_replaces_invalid_sequences() {
+    let service = LogUtf8LossyService::info("bytes: ");
+    let messages = messages(|| {
+        assert_eq!(service.process(&b"valid"[..]), Ok(&b"valid"[..]));
+        assert_eq!(
+            service.process(&b"in\xffvalid"[..]),
+            Ok(&b"in\xffvalid"[..])
+        );
+        set_max_level(LevelFilter::Warn);
+        assert_eq!(service.process(&b"skipped"[..]), Ok(&b"skipped"[..]));
+    });
+    assert_eq!(messages, vec!["bytes: valid", "bytes: in\u{fffd}valid"]);
+}
+
+#[test]
+fn size_reports_shallow_size() {
+    let service = LogSizeService::info("payload size: ");
+    let messages = messages(|| assert_eq!(service.process([0u8; 256]), Ok([0u8; 256])));
+    assert_eq!(messages, vec!["payload size: 256 bytes"]);
+}
+
+#[test]
+fn log_on_drop_logs_when_scope_ends() {
+    let messages = messages(|| {
+        {
+            let service = LogDisplayService::info("stage: ").log_on_drop("stage torn down");
+            assert_eq!(service.process(1), Ok(1));
+        }
+        log::info!("after scope");
+    });
+    assert_eq!(messages, vec!["stage: 1", "stage torn down", "after scope"]);
+}
+
+#[test]
+fn preview_bounds_logged_elements() {
+    let service = LogPreviewService::info("preview: ").with_preview_len(3);
+    let messages = messages(|| {
+        assert_eq!(service.process(vec![1, 2]), Ok(vec![1, 2]));
+        let long: Vec<u32> = (1..=1000).collect();
+        assert_eq!(service.process(long.clone()), Ok(long));
+    });
+    assert_eq!(
+        messages,
+        vec!["preview: [1, 2]", "preview: [1, 2, 3, … (+997 more)]"]
+    );
+}
+
+#[test]
+fn thread_info_tags_each_thread() {
+    let service = LogDisplayService::info("thread: ").with_thread_info();
+    let (named, (id, unnamed)) = std::thread::scope(|scope| {
+        let named = std::thread::Builder::new()
+            .name("worker".to_owned())
+            .spawn_scoped(scope, || messages(|| assert_eq!(service.process(1), Ok(1))))
+            .unwrap();
+        let unnamed = scope.spawn(|| {
+            let id = std::thread::current().id();
+            (id, messages(|| assert_eq!(service.process(2), Ok(2))))
+        });
+        (named.join().unwrap(), unnamed.join().unwrap())
+    });
+    assert_eq!(named, vec!["[worker] thread: 1"]);
+    assert_eq!(unnamed, vec![format!("[{:?}] thread: 2", id)]);
+}
+
+#[test]
+fn number_groups_thousands() {
+    let service = LogNumberService::info("count: ");
+    let messages = messages(|| {
+        assert_eq!(service.process(1234567), Ok(1234567));
+        assert_eq!(service.process(-1234567), Ok(-1234567));
+        assert_eq!(service.process(123), Ok(123));
+    });
+    assert_eq!(
+        messages,
+        vec!["count: 1,234,567", "count: -1,234,567", "count: 123"]
+    );
+}
+
+/// A non-`Copy` value that counts how many times it is cloned
+struct CloneCounter<'c>(&'c AtomicU64);
+impl Clone for CloneCounter<'_> {
+    fn clone(&self) -> Self {
+        self.0.fetch_add(1, Ordering::Relaxed);
+        Self(self.0)
+    }
+}
+impl Display for CloneCounter<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("counter")
+    }
+}
+impl Debug for CloneCounter<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("CloneCounter")
+    }
+}
+
+#[test]
+fn input_is_passed_through_without_cloning() {
+    let clones = AtomicU64::new(0);
+    let messages = messages(|| {
+        let input = LogDisplayService::info("display: ")
+            .process(CloneCounter(&clones))
+            .unwrap();
+        let input = LogDebugService::info("debug: ").process(input).unwrap();
+        let input = LogOptionalDisplayService::info("optional: ")
+            .process(Some(input))
+            .unwrap();
+        assert!(input.is_some());
+    });
+    assert_eq!(
+        messages,
+        vec![
+            "display: counter",
+            "debug: CloneCounter",
+            "optional: counter"
+        ]
+    );
+    assert_eq!(clones.load(Ordering::Relaxed), 0);
+}
+
+#[test]
+fn prefix_fn_is_only_called_when_enabled() {
+    let request_id = AtomicU64::new(0);
+    let service = LogDisplayService::from_prefix_fn(Level::Debug, || {
+        Cow::Owned(format!(
+            "request {}: ",
+            request_id.fetch_add(1, Ordering::Relaxed)
+        ))
+    });
+    let messages = messages(|| {
+        assert_eq!(service.process("a"), Ok("a"));
+        set_max_level(LevelFilter::Info);
+        assert_eq!(service.process("b"), Ok("b"));
+        set_max_level(LevelFilter::Debug);
+        assert_eq!(service.process("c"), Ok("c"));
+    });
+    assert_eq!(messages, vec!["request 0: a", "request 1: c"]);
+    assert_eq!(request_id.load(Ordering::Relaxed), 2);
+}
+
+#[test]
+fn level_tag_names_each_level() {
+    let messages = messages(|| {
+        for level in [
+            Level::Error,
+            Level::Warn,
+            Level::Info,
+            Level::Debug,
+            Level::Trace,
+        ] {
+            let service = LogDisplayService::new(level, "tagged: ").with_level_tag();
+            assert_eq!(service.process(1), Ok(1));
+        }
+    });
+    assert_eq!(
+        messages,
+        vec![
+            "[ERROR] tagged: 1",
+            "[WARN] tagged: 1",
+            "[INFO] tagged: 1",
+            "[DEBUG] tagged: 1",
+            "[TRACE] tagged: 1"
+        ]
+    );
+}
+
+#[test]
+fn ring_buffer_retains_the_most_recent_statements() {
+    let service = RingBufferLogDisplayService::info("recent: ", 3);
+    let messages = messages(|| {
+        for n in 0..5 {
+            assert_eq!(service.process(n), Ok(n));
+        }
+        set_max_level(LevelFilter::Warn);
+        assert_eq!(service.process(5), Ok(5));
+    });
+    assert_eq!(messages.len(), 5);
+    assert_eq!(
+        service.recent(),
+        vec!["recent: 2", "recent: 3", "recent: 4"]
+    );
+}
+
+#[test]
+fn dedup_suppresses_values_within_the_window() {
+    let service = LogDedupService::info("alert: ", Duration::from_secs(60));
+    let messages = messages(|| {
+        for value in ["a", "b", "a", "b", "c"] {
+            assert_eq!(service.process(value), Ok(value));
+        }
+    });
+    assert_eq!(messages, vec!["alert: a", "alert: b", "alert: c"]);
+}
+
+#[test]
+fn dedup_logs_values_again_after_the_window() {
+    let ttl = Duration::from_secs(60);
+    let service = LogDedupService::info("alert: ", ttl);
+    let messages = messages(|| {
+        assert_eq!(service.process("a"), Ok("a"));
+        assert_eq!(service.process("a"), Ok("a"));
+        for last in service.logged.lock().unwrap().values_mut() {
+            *last -= ttl;
+        }
+        assert_eq!(service.process("a"), Ok("a"));
+        assert_eq!(service.process("a"), Ok("a"));
+    });
+    assert_eq!(messages, vec!["alert: a", "alert: a"]);
+}
+
+#[test]
+fn dedup_evicts_the_oldest_value_when_full() {
+    let service = LogDedupService::info("alert: ", Duration::from_secs(60)).with_max_entries(2);
+    let messages = messages(|| {
+        for value in ["a", "b", "c", "c", "a"] {
+            assert_eq!(service.process(value), Ok(value));
+        }
+    });
+    assert_eq!(
+        messages,
+        vec!["alert: a", "alert: b", "alert: c", "alert: a"]
+    );
+    assert_eq!(service.logged.lock().unwrap().len(), 2);
+}
+
+#[cfg(feature = "tracing")]
+#[test]
+fn tracing_events_reach_the_subscriber() {
+    let records = capture(|| {
+        let service = LogDisplayService::warn("value: ").with_target("app::events");
+        assert_eq!(service.process(1), Ok(1));
+    });
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0].level, Level::Warn);
+    assert_eq!(records[0].target, "app::events");
+    assert_eq!(records[0].message, "value: 1");
+}
+
+#[test]
+fn duration_is_formatted_compactly() {
+    let service = LogDurationService::info("took ");
+    let messages = messages(|| {
+        for duration in [
+            Duration::from_micros(1500),
+            Duration::from_millis(1234),
+            Duration::from_secs(184),
+            Duration::from_secs(3725),
+            Duration::from_nanos(999),
+            Duration::from_nanos(999_999),
+            Duration::from_nanos(999_999_999),
+            Duration::from_millis(59_994),
+            Duration::from_micros(59_999_999),
+        ] {
+            assert_eq!(service.process(duration), Ok(duration));
+        }
+    });
+    assert_eq!(
+        messages,
+        vec![
+            "took 1.50ms",
+            "took 1.23s",
+            "took 3m 4s",
+            "took 1h 2m 5s",
+            "took 999ns",
+            "took 1.00ms",
+            "took 1.00s",
+            "took 59.99s",
+            "took 1m 0s"
+        ]
+    );
+}
+
+#[test]
+fn duration_precision_and_unit_are_configurable() {
+    let precise = LogDurationService::info("took ").with_precision(4);
+    let millis = LogDurationService::info("took ").with_unit(DurationUnit::Millis);
+    let messages = messages(|| {
+        let duration = Duration::from_nanos(1_234_567_891);
+        assert_eq!(precise.process(duration), Ok(duration));
+        assert_eq!(millis.process(duration), Ok(duration));
+    });
+    assert_eq!(messages, vec!["took 1.2346s", "took 1234ms"]);
+}
+
+#[test]
+fn transition_logs_only_changes() {
+    #[derive(Debug, Clone, PartialEq)]
+    enum State {
+        A,
+        B,
+        C,
+    }
+    let service = LogTransitionService::info("state: ");
+    let messages = messages(|| {
+        for state in [State::A, State::A, State::B, State::B, State::C] {
+            assert_eq!(service.process(state.clone()), Ok(state));
+        }
+    });
+    assert_eq!(
+        messages,
+        vec!["state: (init) -> A", "state: A -> B", "state: B -> C"]
+    );
+}
+
+#[test]
+fn or_err_returns_error_for_rejected_input() {
+    let service = LogOrErrService::info("checked: ", |n: &i32| *n < 0, "negative");
+    let messages = messages(|| {
+        assert_eq!(service.process(1), Ok(1));
+        assert_eq!(service.process(-1), Err("negative"));
+    });
+    assert_eq!(messages, vec!["checked: 1", "checked: -1"]);
+}
+
+#[test]
+fn required_optional_warns_and_errors_on_none() {
+    let optional = LogOptionalDisplayService::info("value: ");
+    let required = LogOptionalDisplayService::info("value: ")
+        .with_target("app::required")
+        .with_required();
+    let records = capture(|| {
+        assert_eq!(optional.process(None::<i32>), Ok(None));
+        assert_eq!(required.process(Some(1)), Ok(Some(1)));
+        assert_eq!(required.process(None), Err(()));
+    });
+    assert_eq!(records.len(), 2);
+    assert_eq!(records[0].message, "value: 1");
+    assert_eq!(records[1].level, Level::Warn);
+    assert_eq!(records[1].target, "app::required");
+    assert_eq!(records[1].message, "value: required input was None");
+}
+
+#[test]
+fn default_prefix_is_prepended_to_subsequently_constructed_services() {
+    // the default prefix is process-global, so it is set in a child process
+    if !is_child() {
+        stderr_of_child("tests::default_prefix_is_prepended_to_subsequently_constructed_services");
+        return;
+    }
+    let before = LogDisplayService::info("value: ");
+    set_default_prefix("app: ");
+    let after = LogDisplayService::info("value: ");
+    let without = LogDisplayService::info("value: ").without_default_prefix();
+    let constant = LogDisplayServiceConst::<_, { Level::Info as usize }>::new("value: ");
+    let messages = messages(|| {
+        assert_eq!(before.process(1), Ok(1));
+        assert_eq!(after.process(2), Ok(2));
+        assert_eq!(without.process(3), Ok(3));
+        assert_eq!(constant.process(4), Ok(4));
+    });
+    assert_eq!(
+        messages,
+        vec!["value: 1", "app: value: 2", "value: 3", "app: value: 4"]
+    );
+}
+
+#[test]
+fn writer_logs_each_line_across_writes() {
+    let messages = messages(|| {
+        let mut writer = LogWriter::info("progress: ");
+        write!(writer, "a\nb").unwrap();
+        write!(writer, "c\r\n\n").unwrap();
+        write!(writer, "d").unwrap();
+        writer.flush().unwrap();
+        write!(writer, "e").unwrap();
+    });
+    assert_eq!(
+        messages,
+        vec![
+            "progress: a",
+            "progress: bc",
+            "progress: ",
+            "progress: d",
+            "progress: e"
+        ]
+    );
+}
+
+#[test]
+fn level_disabled_mid_flight_is_handled_gracefully() {
+    let service = LogDisplayService::from_prefix_fn(Level::Info, || {
+        // disables the level after the service checked that it is enabled
+        set_max_level(LevelFilter::Off);
+        Cow::Borrowed("value: ")
+    });
+    let messages = messages(|| {
+        assert_eq!(service.process(1), Ok(1));
+        set_max_level(LevelFilter::Trace);
+        assert_eq!(service.process(2), Ok(2));
+    });
+    assert!(messages.is_empty(), "{:?}", messages);
+}
+
+#[test]
+fn duration_is_extracted_and_logged_in_a_fixed_unit() {
+    struct Request {
+        elapsed: Duration,
+    }
+    let messages = messages(|| {
+        for unit in [
+            DurationUnit::Millis,
+            DurationUnit::Micros,
+            DurationUnit::Nanos,
+        ] {
+            let service =
+                LogDurationService::from_fn(Level::Info, "elapsed: ", |request: &Request| {
+                    request.elapsed
+                })
+                .with_unit(unit);
+            let request = Request {
+                elapsed: Duration::from_nanos(1_234_567),
+            };
+            assert!(service.process(request).is_ok());
+        }
+    });
+    assert_eq!(
+        messages,
+        vec!["elapsed: 1ms", "elapsed: 1234µs", "elapsed: 1234567ns"]
+    );
+}
+
+#[test]
+fn variant_is_logged_by_name() {
+    enum Connection {
+        Connecting,
+        Connected(u32),
+        Closed { reason: String },
+    }
+    let service = LogVariantService::info("state: ").with_namer(|connection| match connection {
+        Connection::Connecting => "Connecting",
+        Connection::Connected(_) => "Connected",
+        Connection::Closed { .. } => "Closed",
+    });
+    let unnamed = LogVariantService::info("state: ");
+    let messages = messages(|| {
+        assert!(service.process(Connection::Connecting).is_ok());
+        assert!(matches!(
+            service.process(Connection::Connected(1)),
+            Ok(Connection::Connected(1))
+        ));
+        let closed = Connection::Closed {
+            reason: "eof".to_owned(),
+        };
+        assert!(matches!(
+            service.process(closed),
+            Ok(Connection::Closed { reason }) if reason == "eof"
+        ));
+        assert!(unnamed.process(Connection::Connected(2)).is_ok());
+    });
+    assert_eq!(
+        &messages[..3],
+        ["state: Connecting", "state: Connected", "state: Closed"]
+    );
+    assert!(
+        messages[3].starts_with("state: Discriminant("),
+        "{}",
+        messages[3]
+    );
+}
+
+#[test]
+fn escalating_logs_recurring_input_at_the_escalated_level() {
+    let service = EscalatingLogDisplayService::new(
+        Level::Debug,
+        Level::Warn,
+        "retry: ",
+        2,
+        Duration::from_secs(60),
+    );
+    let records = capture(|| {
+        for value in ["a", "a", "b", "a", "a", "b"] {
+            assert_eq!(service.process(value), Ok(value));
+        }
+    });
+    let logged: Vec<(Level, &str)> = records
+        .iter()
+        .map(|record| (record.level, record.message.as_str()))
+        .collect();
+    assert_eq!(
+        logged,
+        vec![
+            (Level::Debug, "retry: a"),
+            (Level::Debug, "retry: a"),
+            (Level::Debug, "retry: b"),
+            (Level::Warn, "retry: a"),
+            (Level::Warn, "retry: a"),
+            (Level::Debug, "retry: b"),
+        ]
+    );
+}
+
+#[test]
+fn escalate_logs_once_per_level_up_to_the_maximum() {
+    let service = LogEscalateService::new(Level::Info, "level: ");
+    let records = capture(|| {
+        assert_eq!(service.process(1), Ok(1));
+    });
+    let levels: Vec<Level> = records.iter().map(|record| record.level).collect();
+    assert_eq!(levels, vec![Level::Trace, Level::Debug, Level::Info]);
+    assert!(records.iter().all(|record| record.message == "level: 1"));
+}
+
+#[cfg(feature = "kv")]
+#[test]
+fn typed_fields_preserve_their_types() {
+    let service = LogFieldsService::info("event: ", |input: &&str| {
+        vec![("action", (*input).to_owned())]
+    })
+    .with_field_i64("attempt", -3)
+    .with_field_f64("ratio", 0.5)
+    .with_field_bool("retry", true);
+    let records = capture(|| assert_eq!(service.process("login"), Ok("login")));
+    assert_eq!(records.len(), 1);
+    if cfg!(feature = "tracing") {
+        assert_eq!(
+            records[0].message,
+            "event: [action=login attempt=-3 ratio=0.5 retry=true]"
+        );
+    } else {
+        assert_eq!(
+            records[0].key_values,
+            vec![
+                ("action".to_owned(), KvValue::Str("login".to_owned())),
+                ("attempt".to_owned(), KvValue::I64(-3)),
+                ("ratio".to_owned(), KvValue::F64(0.5)),
+                ("retry".to_owned(), KvValue::Bool(true)),
+            ]
+        );
+    }
+}
+
+#[test]
+fn capture_retains_the_last_message() {
+    let service = LogDisplayService::info("value: ").with_capture();
+    assert_eq!(service.last_message(), None);
+    capture(|| {
+        assert_eq!(service.process(1), Ok(1));
+        assert_eq!(service.process(2), Ok(2));
+    });
+    assert_eq!(service.last_message(), Some("value: 2".to_owned()));
+    assert_eq!(LogDisplayService::<i32>::info("").last_message(), None);
+}
+
+#[test]
+fn size_of_u64_and_estimated_heap_size() {
+    let service = LogSizeService::info("size=");
+    let heap = LogSizeService::info("size=").with_heap_size(|v: &Vec<u64>| v.capacity() * 8);
+    let messages = messages(|| {
+        assert_eq!(service.process(7u64), Ok(7u64));
+        let vec = Vec::with_capacity(4);
+        assert!(heap.process(vec).is_ok());
+    });
+    assert_eq!(
+        messages,
+        vec![
+            "size=8 bytes".to_owned(),
+            format!("size={} bytes (+32 heap)", std::mem::size_of::<Vec<u64>>())
+        ]
+    );
+}
+
+#[test]
+fn syslog_severity_maps_each_level() {
+    assert_eq!(syslog_severity(Level::Error), 3);
+    assert_eq!(syslog_severity(Level::Warn), 4);
+    assert_eq!(syslog_severity(Level::Info), 6);
+    assert_eq!(syslog_severity(Level::Debug), 7);
+    assert_eq!(syslog_severity(Level::Trace), 7);
+    let messages = messages(|| {
+        let service = LogDisplayService::warn("disk: ").with_syslog_severity();
+        assert_eq!(service.process("full"), Ok("full"));
+    });
+    assert_eq!(messages, vec!["<4>disk: full"]);
+}
+
+#[test]
+fn rate_logs_throughput_when_the_interval_elapses() {
+    let service = LogRateService::info("throughput: ", Duration::from_secs(1));
+    let messages = messages(|| {
+        for n in 0..9 {
+            assert_eq!(service.process(n), Ok(n));
+        }
+        service.throughput.window.lock().unwrap().0 -= Duration::from_secs(1);
+        assert_eq!(service.process(9), Ok(9));
+        assert_eq!(service.process(10), Ok(10));
+    });
+    assert_eq!(messages, vec!["throughput: 10 msg/s"]);
+}
+
+#[test]
+fn paired_level_logs_at_the_provided_level() {
+    let service = LogPairedLevelService::new("validation: ");
+    let records = capture(|| {
+        assert_eq!(service.process((Level::Warn, "msg")), Ok("msg"));
+        assert_eq!(service.process((Level::Debug, "ok")), Ok("ok"));
+    });
+    let logged: Vec<(Level, &str)> = records
+        .iter()
+        .map(|record| (record.level, record.message.as_str()))
+        .collect();
+    assert_eq!(
+        logged,
+        vec![
+            (Level::Warn, "validation: msg"),
+            (Level::Debug, "validation: ok")
+        ]
+    );
+}
+
+#[test]
+fn histogram_logs_percentiles_in_expected_buckets() {
+    let service = HistogramLogService::info("latency: ", vec![10.0, 50.0, 100.0], 100);
+    let messages = messages(|| {
+        for value in (1..=97u32).chain([150, 180, 200]) {
+            assert_eq!(service.process(value), Ok(value));
+        }
+        assert_eq!(service.process(1), Ok(1));
+    });
+    assert_eq!(messages, vec!["latency: p50=50 p95=100 p99=200"]);
+}
+
+#[test]
+fn tuple_elements_are_labeled() {
+    let pair = LogTupleService::<(_, _)>::info("entry: ", ["key", "value"]);
+    let triple = LogTupleService::<(_, _, _)>::info("point: ", ["x", "y"]);
+    let messages = messages(|| {
+        assert_eq!(pair.process(("foo", 42)), Ok(("foo", 42)));
+        assert_eq!(triple.process((1, 2, 3)), Ok((1, 2, 3)));
+    });
+    assert_eq!(
+        messages,
+        vec!["entry: (key=foo, value=42)", "point: (x=1, y=2, 2=3)"]
+    );
+}
+
+#[test]
+fn threshold_crossings_are_logged_once_per_transition() {
+    let service = ThresholdCrossingLogService::new(Level::Warn, "cpu: ", 80);
+    let messages = messages(|| {
+        for value in [50, 70, 90, 95, 60, 85, 85, 10] {
+            assert_eq!(service.process(value), Ok(value));
+        }
+    });
+    assert_eq!(
+        messages,
+        vec![
+            "cpu: crossed above 80",
+            "cpu: crossed below 80",
+            "cpu: crossed above 80",
+            "cpu: crossed below 80"
+        ]
+    );
+}
+
+#[test]
+fn sink_receives_level_and_message() {
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let sink: LogSink = {
+        let received = Arc::clone(&received);
+        Arc::new(move |level, message| {
+            received.lock().unwrap().push((level, message.to_owned()));
+        })
+    };
+    let service = LogSinkService::warn("sink: ", sink);
+    assert_eq!(service.process(1), Ok(1));
+    assert_eq!(
+        *received.lock().unwrap(),
+        vec![(Level::Warn, "sink: 1".to_owned())]
+    );
+}
+
+#[test]
+fn try_new_non_empty_rejects_empty_prefixes() {
+    assert_eq!(
+        LogDisplayService::<u32>::try_new_non_empty(Level::Info, "").err(),
+        Some(PrefixError::Empty)
+    );
+    assert_eq!(
+        LogDisplayService::<u32>::try_new_non_empty(Level::Info, " \t").err(),
+        Some(PrefixError::Empty)
+    );
+    assert_eq!(
+        PrefixError::Empty.to_string(),
+        "prefix is empty or only whitespace"
+    );
+    let service = LogDisplayService::try_new_non_empty(Level::Info, "valid: ").unwrap();
+    let messages = messages(|| assert_eq!(service.process(1), Ok(1)));
+    assert_eq!(messages, vec!["valid: 1"]);
+}
+
+#[test]
+fn fill_rate_reports_the_percentage_of_some() {
+    let service = OptionFillRateLogService::info("optional: ", 4);
+    let messages = messages(|| {
+        for input in [Some(1), None, Some(2), Some(3), None, None, None, Some(4)] {
+            assert_eq!(service.process(input), Ok(input));
+        }
+    });
+    assert_eq!(messages, vec!["optional: fill=75%", "optional: fill=25%"]);
+}
+
+#[test]
+fn ok_field_logs_only_the_ok_branch() {
+    struct Response {
+        status: u16,
+    }
+    let service = LogOkFieldService::info("status: ", |response: &Response| {
+        response.status.to_string()
+    });
+    let messages = messages(|| {
+        let ok: Result<Response, &str> = Ok(Response { status: 200 });
+        assert!(matches!(
+            service.process(ok),
+            Ok(Ok(Response { status: 200 }))
+        ));
+        assert!(matches!(
+            service.process(Err("timeout")),
+            Ok(Err("timeout"))
+        ));
+    });
+    assert_eq!(messages, vec!["status: 200"]);
+}
+
+#[test]
+fn ids_are_unique_and_tag_each_service() {
+    let first = LogDisplayService::info("stage: ").with_id();
+    let second = LogDisplayService::info("stage: ").with_id();
+    assert_ne!(first.id(), second.id());
+    let messages = messages(|| {
+        assert_eq!(first.process(1), Ok(1));
+        assert_eq!(second.process(2), Ok(2));
+    });
+    assert_eq!(
+        messages,
+        vec![
+            format!("#{} stage: 1", first.id()),
+            format!("#{} stage: 2", second.id())
+        ]
+    );
+}
+
+#[test]
+fn counter_is_incremented_for_each_input() {
+    let counter = Arc::new(AtomicU64::new(0));
+    let service = LogDisplayService::debug("counted: ").with_counter(Arc::clone(&counter));
+    let messages = messages(|| {
+        assert_eq!(service.process(1), Ok(1));
+        set_max_level(LevelFilter::Info);
+        assert_eq!(service.process(2), Ok(2));
+    });
+    assert_eq!(messages, vec!["counted: 1"]);
+    assert_eq!(counter.load(Ordering::Relaxed), 2);
+    assert!(Arc::ptr_eq(service.counter().unwrap(), &counter));
+}
+
+#[test]
+fn renderer_can_be_swapped() {
+    let service = RenderLogService::info("rendered: ", Box::new(|n: &u32| format!("{}", n)));
+    let messages = messages(|| {
+        assert_eq!(service.process(255), Ok(255));
+        service.set_renderer(Box::new(|n: &u32| format!("{:#x}", n)));
+        assert_eq!(service.process(255), Ok(255));
+    });
+    assert_eq!(messages, vec!["rendered: 255", "rendered: 0xff"]);
+}
+
+#[test]
+fn clone_tee_returns_the_input_twice() {
+    let service = LogCloneTeeService::info("fan out: ");
+    let records = capture(|| {
+        assert_eq!(
+            service.process("value".to_owned()),
+            Ok(("value".to_owned(), "value".to_owned()))
+        );
+    });
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0].message, "fan out: value");
+}
+
+#[test]
+fn distinct_errors_are_logged_once() {
+    let service = LogDistinctErrorService::error("request failed: ");
+    let messages = messages(|| {
+        for input in [
+            Err("timeout"),
+            Err("timeout"),
+            Ok(1),
+            Err("timeout"),
+            Err("refused"),
+            Err("refused"),
+        ] {
+            assert_eq!(service.process(input), Ok(input));
+        }
+    });
+    assert_eq!(
+        messages,
+        vec!["request failed: timeout", "request failed: refused"]
+    );
+}
+
+#[test]
+fn width_pads_the_formatted_value() {
+    let right = LogDisplayService::info("code: ")
+        .with_width(8)
+        .with_alignment(fmt::Alignment::Right);
+    let left = LogDisplayService::info("code: ").with_width(8);
+    let messages = messages(|| {
+        assert_eq!(right.process("42"), Ok("42"));
+        assert_eq!(left.process("42"), Ok("42"));
+        assert_eq!(right.process("123456789"), Ok("123456789"));
+    });
+    assert_eq!(
+        messages,
+        vec!["code:       42", "code: 42      ", "code: 123456789"]
+    );
+}
+
+#[test]
+fn epoch_tags_change_between_batches() {
+    let service = EpochLogDisplayService::info("item: ");
+    let epoch = service.epoch_handle();
+    let messages = messages(|| {
+        assert_eq!(service.process(1), Ok(1));
+        epoch.fetch_add(1, Ordering::Relaxed);
+        assert_eq!(service.process(2), Ok(2));
+    });
+    assert_eq!(messages, vec!["item: [epoch 0] 1", "item: [epoch 1] 2"]);
+}
+
+#[test]
+fn shared_clones_log_from_each_thread() {
+    let service = LogDisplayServiceShared::info("shared: ");
+    let handles: Vec<_> = (0..2)
+        .map(|thread| {
+            let service = service.clone();
+            std::thread::spawn(move || messages(|| assert_eq!(service.process(thread), Ok(thread))))
+        })
+        .collect();
+    let logged: Vec<Vec<String>> = handles
+        .into_iter()
+        .map(|handle| handle.join().unwrap())
+        .collect();
+    assert_eq!(logged, vec![vec!["shared: 0"], vec!["shared: 1"]]);
+    assert_eq!(service.prefix(), "shared: ");
+    assert_eq!(
+        service.to_string(),
+        "LogDisplayServiceShared(level=INFO, prefix=\"shared: \")"
+    );
+}
+
+#[test]
+fn rate_is_logged_as_a_key_value_when_configured() {
+    let service =
+        LogRateService::info("ingest ", Duration::from_secs(2)).with_format(RateFormat::KeyValue);
+    let messages = messages(|| {
+        for n in 0..99 {
+            assert_eq!(service.process(n), Ok(n));
+        }
+        // simulates the report interval elapsing
+        service.throughput.window.lock().unwrap().0 -= Duration::from_secs(2);
+        assert_eq!(service.process(99), Ok(99));
+        assert_eq!(service.process(100), Ok(100));
+    });
+    assert_eq!(messages, vec!["ingest rate=50/s"]);
+}
+
+#[test]
+fn delta_of_unsigned_input_is_signed() {
+    let service = LogDeltaService::info("delta: ");
+    let messages = messages(|| {
+        for input in [10u64, 15, 12, 12] {
+            assert_eq!(service.process(input), Ok(input));
+        }
+    });
+    assert_eq!(
+        messages,
+        vec!["delta: initial 10", "delta: +5", "delta: -3", "delta: +0"]
+    );
+}
+
+#[test]
+fn transformed_input_is_formatted_in_the_reused_buffer() {
+    struct MultiLine;
+    impl Debug for MultiLine {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str("a  b\nc ")
+        }
+    }
+    let services = [
+        LogDisplayService::info("width: ")
+            .with_width(12)
+            .with_alignment(fmt::Alignment::Center),
+        LogDisplayService::info("strip: ").with_strip_newlines(),
+        LogDisplayService::info("trim: ")
+            .with_trim()
+            .with_max_len(4),
+    ];
+    let collapse = LogDebugService::info("collapse: ").with_single_line();
+    let input = "a  b\nc ";
+    let log = || {
+        for service in &services {
+            assert_eq!(service.process(input), Ok(input));
+        }
+        assert!(collapse.process(MultiLine).is_ok());
+    };
+    assert_eq!(
+        messages(log),
+        vec![
+            format!("width: {:^12}", input),
+            "strip: a  b c ".to_owned(),
+            "trim: a  b…".to_owned(),
+            "collapse: a b c".to_owned()
+        ]
+    );
+}
+
+#[test]
+fn context_is_attached_to_each_record() {
+    let service = LogDisplayService::info("event: ").with_context(vec![
+        ("service", "billing".to_owned()),
+        ("env", "prod".to_owned()),
+    ]);
+    let records = capture(|| {
+        assert_eq!(service.process(1), Ok(1));
+        assert_eq!(service.process(2), Ok(2));
+    });
+    assert_eq!(records.len(), 2);
+    for (record, input) in records.iter().zip(1..) {
+        #[cfg(all(feature = "kv", not(feature = "tracing")))]
+        {
+            assert_eq!(record.message, format!("event: {}", input));
+            assert_eq!(
+                record.key_values,
+                vec![
+                    ("service".to_owned(), KvValue::Str("billing".to_owned())),
+                    ("env".to_owned(), KvValue::Str("prod".to_owned())),
+                ]
+            );
+        }
+        #[cfg(not(all(feature = "kv", not(feature = "tracing"))))]
+        assert_eq!(
+            record.message,
+            format!("[service=billing env=prod] event: {}", input)
+        );
+    }
+}
+
+#[test]
+fn context_is_rendered_without_a_record() {
+    let context = || vec![("service", "billing".to_owned())];
+    if !is_child() {
+        let captured = LogDisplayService::info("event: ")
+            .with_context(context())
+            .with_capture();
+        let _ = messages(|| assert_eq!(captured.process(1), Ok(1)));
+        assert_eq!(
+            captured.last_message().as_deref(),
+            Some("[service=billing] event: 1")
+        );
+        let stderr = stderr_of_child("tests::context_is_rendered_without_a_record");
+        assert!(
+            stderr.contains("[service=billing] event: 2\n"),
+            "{}",
+            stderr
+        );
+        return;
+    }
+    let service = LogDisplayService::info("event: ")
+        .with_context(context())
+        .with_fallback_stderr();
+    assert_eq!(service.process(2), Ok(2));
+}
+
+#[test]
+fn single_line_collapses_multi_line_debug() {
+    /// A value whose [`Debug`] impl emits newlines without `{:#?}`
+    struct Pretty(Vec<u32>);
+    impl Debug for Pretty {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "{:#?}", self.0)
+        }
+    }
+    let service = LogDebugService::info("values: ").with_single_line();
+    let messages = messages(|| assert!(service.process(Pretty(vec![1, 2])).is_ok()));
+    assert_eq!(messages, vec!["values: [ 1, 2, ]"]);
+}
+
+#[cfg(feature = "debug-tools")]
+#[test]
+fn pace_returns_after_the_delay() {
+    let delay = Duration::from_millis(20);
+    let service = LogPaceService::new(Level::Info, "paced: ", delay);
+    let start = Instant::now();
+    let messages = messages(|| assert_eq!(service.process(1), Ok(1)));
+    assert!(start.elapsed() >= delay);
+    assert_eq!(messages, vec!["paced: 1"]);
+}
+
+#[test]
+fn deferred_logs_only_on_commit() {
+    let service = DeferredLogDisplayService::info("tx: ");
+    let messages = messages(|| {
+        assert_eq!(service.process("insert"), Ok("insert"));
+        assert_eq!(service.process("update"), Ok("update"));
+        assert!(CAPTURED.with(|records| records.borrow().is_empty()));
+        service.commit();
+        assert_eq!(service.process("delete"), Ok("delete"));
+        service.rollback();
+        service.commit();
+    });
+    assert_eq!(messages, vec!["tx: insert", "tx: update"]);
+}
+
+#[test]
+fn first_n_logs_only_up_to_the_limit() {
+    let none = LogFirstNService::new(Level::Info, "none: ", 0);
+    let two = LogFirstNService::new(Level::Info, "two: ", 2);
+    let messages = messages(|| {
+        for input in 0..3 {
+            assert_eq!(none.process(input), Ok(input));
+            assert_eq!(two.process(input), Ok(input));
+        }
+    });
+    assert_eq!(messages, vec!["two: 0", "two: 1"]);
+}
+
+#[test]
+fn cyclic_index_cycles_through_slots() {
+    let service = CyclicIndexLogService::info("shard ", NonZeroU64::new(3).unwrap());
+    let messages = messages(|| {
+        for input in 0..5 {
+            assert_eq!(service.process(input), Ok(input));
+        }
+    });
+    assert_eq!(
+        messages,
+        vec![
+            "shard slot=0",
+            "shard slot=1",
+            "shard slot=2",
+            "shard slot=0",
+            "shard slot=1"
+        ]
+    );
+}
+
+#[test]
+fn log_chain_logs_between_stages() {
+    let add_one = FnService::new(|x: u32| Ok::<_, ()>(x + 1));
+    let double = FnService::new(|x: u32| Ok::<_, ()>(x * 2));
+    let chain = crate::log_chain!(info, add_one, double);
+    let messages = messages(|| assert_eq!(chain.process(1).unwrap(), 4));
+    assert_eq!(messages, vec!["into double: 2"]);
+}
+
+#[test]
+fn into_logs_then_converts() {
+    let service = LogIntoService::<u8, u32>::info("byte: ");
+    let messages = messages(|| {
+        let converted: Result<u32, ()> = service.process(255u8);
+        assert_eq!(converted, Ok(255u32));
+    });
+    assert_eq!(messages, vec!["byte: 255"]);
+}
+
+#[test]
+fn log_display_attributes_records_to_the_caller() {
+    let records = capture(|| assert_eq!(caller::log_display().process(1), Ok(1)));
+    assert_eq!(records.len(), 1);
+    let caller = concat!(module_path!(), "::caller");
+    assert_eq!(records[0].target, caller);
+    assert_eq!(records[0].module_path.as_deref(), Some(caller));
+    assert_eq!(records[0].file.as_deref(), Some(file!()));
+    assert_eq!(records[0].line, Some(caller::LINE));
+    assert_eq!(records[0].message, "display: 1");
+}
+
+#[test]
+fn map_logs_input_and_output() {
+    let service = LogMapService::info("in: ", "out: ", |x: i32| format!("<{}>", x * 2));
+    let messages = messages(|| assert_eq!(service.process(-21), Ok("<-42>".to_owned())));
+    assert_eq!(messages, vec!["in: -21", "out: <-42>"]);
+}
+
+#[test]
+fn suppressible_skips_no_log_values() {
+    let service = SuppressibleLogService::new(LogDisplayService::info("value: "));
+    let messages = messages(|| {
+        assert_eq!(service.process(Loggable::Log(1)), Ok(1));
+        assert_eq!(service.process(NoLog(2).into()), Ok(2));
+        assert_eq!(service.process(Loggable::Skip(3)), Ok(3));
+        assert_eq!(service.process(Loggable::Log(4)), Ok(4));
+    });
+    assert_eq!(messages, vec!["value: 1", "value: 4"]);
+}
+
+#[test]
+fn alert_target_is_logged_once_the_error_rate_exceeds_the_threshold() {
+    let window = NonZeroUsize::new(4).unwrap();
+    let service =
+        AdaptiveAlertLogResultService::<u32, &str>::warn("failed: ", "alert", window, 0.5);
+    let records = capture(|| {
+        assert_eq!(service.process(Err("a")), Ok(Err("a")));
+        assert_eq!(service.process(Ok(1)), Ok(Ok(1)));
+        assert_eq!(service.process(Err("b")), Ok(Err("b")));
+        assert_eq!(service.process(Err("c")), Ok(Err("c")));
+    });
+    let alerts: Vec<_> = records.iter().filter(|r| r.target == "alert").collect();
+    assert_eq!(alerts.len(), 1);
+    assert_eq!(alerts[0].level, Level::Error);
+    assert_eq!(alerts[0].message, "failed: error rate 75%: c");
+    let errors: Vec<_> = records.iter().filter(|r| r.target != "alert").collect();
+    assert_eq!(errors.len(), 3);
+    assert!(errors.iter().all(|r| r.level == Level::Warn));
+}
+
+#[test]
+#[should_panic(expected = "threshold must be between 0.0 and 1.0")]
+fn alert_threshold_outside_of_the_unit_range_is_rejected() {
+    let window = NonZeroUsize::new(4).unwrap();
+    AdaptiveAlertLogResultService::<u32, &str>::warn("failed: ", "alert", window, 1.5);
+}
+
+#[test]
+fn logfmt_leaves_simple_values_unquoted() {
+    let service = LogLogfmtService::info("event");
+    let messages = messages(|| assert_eq!(service.process("hello"), Ok("hello")));
+    assert_eq!(messages, vec!["level=info prefix=event value=hello"]);
+}
+
+#[test]
+fn logfmt_quotes_and_escapes_values() {
+    let service = LogLogfmtService::warn("my event");
+    let messages = messages(|| {
+        assert_eq!(
+            service.process(r#"say "hi" \ bye"#),
+            Ok(r#"say "hi" \ bye"#)
+        );
+        assert_eq!(service.process(""), Ok(""));
+    });
+    assert_eq!(
+        messages,
+        vec![
+            r#"level=warn prefix="my event" value="say \"hi\" \\ bye""#,
+            r#"level=warn prefix="my event" value="""#,
+        ]
+    );
+}
+
+#[test]
+fn logfmt_folds_the_default_prefix_into_the_prefix_value() {
+    // the default prefix is process-global, so it is set in a child process
+    if !is_child() {
+        stderr_of_child("tests::logfmt_folds_the_default_prefix_into_the_prefix_value");
+        return;
+    }
+    set_default_prefix("app: ");
+    let service = LogLogfmtService::info("event");
+    let messages = messages(|| assert_eq!(service.process(1), Ok(1)));
+    assert_eq!(messages, vec![r#"level=info prefix="app: event" value=1"#]);
+}
+
+#[test]
+fn dynamic_prefix_is_produced_for_each_input() {
+    let calls = AtomicU64::new(0);
+    let service = DynamicPrefixLogDisplayService::info(|| {
+        format!("task {}: ", calls.fetch_add(1, Ordering::Relaxed))
+    });
+    let messages = messages(|| {
+        assert_eq!(service.process("a"), Ok("a"));
+        assert_eq!(service.process("b"), Ok("b"));
+    });
+    assert_eq!(messages, vec!["task 0: a", "task 1: b"]);
+}
@@ -0,0 +1,172 @@
+//! Tests that log statements are formatted without allocating.
+//!
+//! Allocations are counted with a global allocator, so these tests run in their own binary rather than counting the allocations of every unit test.
+
+use log::LevelFilter;
+use sod::Service;
+use sod_log::{LogDebugService, LogDisplayService};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::fmt::{self, Debug, Display};
+use std::io::{self, Write};
+use std::sync::Once;
+
+thread_local! {
+    static ALLOCATIONS: Cell<u64> = const { Cell::new(0) };
+    static MAX_LEVEL: Cell<LevelFilter> = const { Cell::new(LevelFilter::Trace) };
+}
+
+/// A [`GlobalAlloc`] that counts the allocations of each thread
+struct CountingAllocator;
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let _ = ALLOCATIONS.try_with(|allocations| allocations.set(allocations.get() + 1));
+        System.alloc(layout)
+    }
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+/// A [`log::Log`] that formats each record up to the thread's maximum level like a logger would, without allocating, and discards it
+struct DiscardLogger;
+impl log::Log for DiscardLogger {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        metadata.level() <= MAX_LEVEL.with(Cell::get)
+    }
+    fn log(&self, record: &log::Record) {
+        let _ = write!(io::sink(), "{}", record.args());
+    }
+    fn flush(&self) {}
+}
+
+/// A [`tracing::Subscriber`] that formats the fields of each event like [`DiscardLogger`], which is set as the default for the duration of [`allocations`]
+#[cfg(feature = "tracing")]
+struct DiscardSubscriber;
+#[cfg(feature = "tracing")]
+impl tracing::Subscriber for DiscardSubscriber {
+    fn register_callsite(
+        &self,
+        _: &'static tracing::Metadata<'static>,
+    ) -> tracing::subscriber::Interest {
+        // the maximum level is per thread, so every event is checked with `enabled`
+        tracing::subscriber::Interest::sometimes()
+    }
+    fn enabled(&self, metadata: &tracing::Metadata) -> bool {
+        let level = match *metadata.level() {
+            tracing::Level::ERROR => log::Level::Error,
+            tracing::Level::WARN => log::Level::Warn,
+            tracing::Level::INFO => log::Level::Info,
+            tracing::Level::DEBUG => log::Level::Debug,
+            _ => log::Level::Trace,
+        };
+        level <= MAX_LEVEL.with(Cell::get)
+    }
+    fn new_span(&self, _: &tracing::span::Attributes) -> tracing::span::Id {
+        tracing::span::Id::from_u64(1)
+    }
+    fn record(&self, _: &tracing::span::Id, _: &tracing::span::Record) {}
+    fn record_follows_from(&self, _: &tracing::span::Id, _: &tracing::span::Id) {}
+    fn event(&self, event: &tracing::Event) {
+        struct Discard;
+        impl tracing::field::Visit for Discard {
+            fn record_debug(&mut self, _: &tracing::field::Field, value: &dyn fmt::Debug) {
+                let _ = write!(io::sink(), "{:?}", value);
+            }
+        }
+        event.record(&mut Discard);
+    }
+    fn enter(&self, _: &tracing::span::Id) {}
+    fn exit(&self, _: &tracing::span::Id) {}
+}
+
+/// Set the maximum level discarded from the current thread, which is reset to [`LevelFilter::Trace`] by [`allocations`]
+fn set_max_level(filter: LevelFilter) {
+    MAX_LEVEL.with(|max_level| max_level.set(filter));
+}
+
+/// Run the given function and return the number of allocations it made, while the records it logs are formatted and discarded
+fn allocations<F: FnOnce()>(f: F) -> u64 {
+    static INIT: Once = Once::new();
+    INIT.call_once(|| {
+        log::set_logger(&DiscardLogger).unwrap();
+        log::set_max_level(LevelFilter::Trace);
+    });
+    set_max_level(LevelFilter::Trace);
+    let count = || {
+        let before = ALLOCATIONS.with(Cell::get);
+        f();
+        ALLOCATIONS.with(Cell::get) - before
+    };
+    #[cfg(not(feature = "tracing"))]
+    return count();
+    #[cfg(feature = "tracing")]
+    return tracing::subscriber::with_default(DiscardSubscriber, count);
+}
+
+#[test]
+fn display_is_formatted_without_allocating() {
+    let service = LogDisplayService::info("value {} ");
+    let counted = allocations(|| {
+        for input in 0..100 {
+            assert_eq!(service.process(input), Ok(input));
+        }
+    });
+    assert_eq!(counted, 0);
+    // retaining the message builds a String, which demonstrates that allocations are counted
+    let captured = LogDisplayService::info("value {} ").with_capture();
+    assert!(allocations(|| assert_eq!(captured.process(1), Ok(1))) > 0);
+    assert_eq!(captured.last_message().as_deref(), Some("value {} 1"));
+}
+
+#[test]
+fn transformed_input_is_formatted_without_allocating() {
+    struct MultiLine;
+    impl Debug for MultiLine {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str("a  b\nc ")
+        }
+    }
+    let services = [
+        LogDisplayService::info("width: ")
+            .with_width(12)
+            .with_alignment(fmt::Alignment::Center),
+        LogDisplayService::info("strip: ").with_strip_newlines(),
+        LogDisplayService::info("trim: ")
+            .with_trim()
+            .with_max_len(4),
+    ];
+    let collapse = LogDebugService::info("collapse: ").with_single_line();
+    let input = "a  b\nc ";
+    let log = || {
+        for service in &services {
+            assert_eq!(service.process(input), Ok(input));
+        }
+        assert!(collapse.process(MultiLine).is_ok());
+    };
+    // the first run sizes the format buffer that is reused by this thread
+    allocations(log);
+    assert_eq!(allocations(log), 0);
+}
+
+#[test]
+fn disabled_level_is_neither_formatted_nor_captured() {
+    struct Unformatted;
+    impl Display for Unformatted {
+        fn fmt(&self, _: &mut fmt::Formatter<'_>) -> fmt::Result {
+            panic!("input of a disabled level was formatted")
+        }
+    }
+    let service = LogDisplayService::info("value: ")
+        .with_trim()
+        .with_capture();
+    let counted = allocations(|| {
+        set_max_level(LevelFilter::Off);
+        assert!(service.process(Unformatted).is_ok());
+    });
+    assert_eq!(counted, 0);
+    assert_eq!(service.last_message(), None);
+}